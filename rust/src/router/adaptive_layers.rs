@@ -0,0 +1,160 @@
+//! Opt-in server-side ABR: an AIMD-style controller that adjusts a [`Consumer`]'s preferred
+//! spatial/temporal layers from its own score and achieved-bitrate reports, instead of requiring
+//! applications to call `set_preferred_layers` themselves.
+//!
+//! The candidate ladder is built once, from the consumer's `consumable_rtp_encodings` dump, when
+//! [`Consumer::enable_adaptive_layers`] is called; the stepping decisions themselves live in
+//! [`AdaptiveLayersState::on_report`] below, which [`Consumer`]'s own `score` notification handler
+//! drives on every report.
+
+use crate::consumer::{ConsumableRtpEncoding, ConsumerLayers, ConsumerScore};
+use std::time::{Duration, Instant};
+
+/// Tuning knobs for [`Consumer::enable_adaptive_layers`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct AdaptiveLayersConfig {
+    /// `ConsumerScore.score` below this value counts as a "low score" report.
+    pub low_score_threshold: u8,
+    /// Consecutive low-score reports (or bitrate estimates under the current layer's
+    /// `max_bitrate`) required before stepping down a layer.
+    pub consecutive_low_reports: u32,
+    /// Consecutive high-score, sufficient-headroom reports required before stepping up a layer.
+    pub stabilization_reports: u32,
+    /// Fraction of extra headroom over the next-higher layer's `max_bitrate` required before
+    /// stepping up (e.g. `0.2` requires 20% margin).
+    pub safety_margin: f64,
+    /// Minimum time between any two layer changes, to prevent oscillation.
+    pub cooldown: Duration,
+}
+
+impl Default for AdaptiveLayersConfig {
+    fn default() -> Self {
+        Self {
+            low_score_threshold: 5,
+            consecutive_low_reports: 3,
+            stabilization_reports: 5,
+            safety_margin: 0.2,
+            cooldown: Duration::from_secs(2),
+        }
+    }
+}
+
+/// One candidate layer and the outgoing bitrate it requires, ordered from lowest to highest
+/// quality.
+#[derive(Debug, Clone, Copy)]
+pub struct LayerCandidate {
+    pub layers: ConsumerLayers,
+    pub max_bitrate: u32,
+}
+
+/// Builds the candidate ladder out of a consumer dump's `consumable_rtp_encodings`: one candidate
+/// per spatial layer, paired with its highest temporal layer (since stepping always asks for the
+/// best temporal layer available within a spatial one), ordered lowest-to-highest by
+/// `max_bitrate`.
+pub(super) fn candidates_from_encodings(encodings: &[ConsumableRtpEncoding]) -> Vec<LayerCandidate> {
+    let mut candidates: Vec<LayerCandidate> = encodings
+        .iter()
+        .enumerate()
+        .map(|(spatial_layer, encoding)| LayerCandidate {
+            layers: ConsumerLayers {
+                spatial_layer: spatial_layer as u8,
+                temporal_layer: encoding
+                    .temporal_layers
+                    .map(|temporal_layers| temporal_layers.saturating_sub(1)),
+            },
+            max_bitrate: encoding.max_bitrate.unwrap_or(0),
+        })
+        .collect();
+
+    candidates.sort_by_key(|candidate| candidate.max_bitrate);
+    candidates
+}
+
+/// Mutable AIMD state driving the layer stepping decisions; not exposed publicly, owned by the
+/// [`Consumer`] and stepped from its `score` notification handler.
+pub(super) struct AdaptiveLayersState {
+    config: AdaptiveLayersConfig,
+    candidates: Vec<LayerCandidate>,
+    current_index: usize,
+    low_streak: u32,
+    high_streak: u32,
+    last_change: Instant,
+}
+
+impl AdaptiveLayersState {
+    pub(super) fn new(
+        config: AdaptiveLayersConfig,
+        candidates: Vec<LayerCandidate>,
+        current_index: usize,
+    ) -> Self {
+        Self {
+            config,
+            candidates,
+            current_index,
+            low_streak: 0,
+            high_streak: 0,
+            last_change: Instant::now(),
+        }
+    }
+
+    /// Feeds a new `(score, available_outgoing_bitrate)` sample; returns the layers to switch to,
+    /// if any.
+    pub(super) fn on_report(
+        &mut self,
+        score: &ConsumerScore,
+        available_outgoing_bitrate: u32,
+    ) -> Option<ConsumerLayers> {
+        if self.candidates.is_empty() {
+            return None;
+        }
+
+        let current = self.candidates[self.current_index];
+        let in_cooldown = self.last_change.elapsed() < self.config.cooldown;
+
+        let is_low = score.score < self.config.low_score_threshold
+            || available_outgoing_bitrate < current.max_bitrate;
+
+        if is_low {
+            self.high_streak = 0;
+            self.low_streak += 1;
+
+            if !in_cooldown
+                && self.low_streak >= self.config.consecutive_low_reports
+                && self.current_index > 0
+            {
+                self.current_index -= 1;
+                self.low_streak = 0;
+                self.last_change = Instant::now();
+                return Some(self.candidates[self.current_index].layers);
+            }
+
+            return None;
+        }
+
+        self.low_streak = 0;
+
+        let next_index = self.current_index + 1;
+        let Some(&next) = self.candidates.get(next_index) else {
+            self.high_streak = 0;
+            return None;
+        };
+
+        let required = next.max_bitrate as f64 * (1.0 + self.config.safety_margin);
+        if (available_outgoing_bitrate as f64) < required {
+            self.high_streak = 0;
+            return None;
+        }
+
+        self.high_streak += 1;
+
+        if !in_cooldown && self.high_streak >= self.config.stabilization_reports {
+            self.current_index = next_index;
+            self.high_streak = 0;
+            self.last_change = Instant::now();
+            return Some(self.candidates[self.current_index].layers);
+        }
+
+        None
+    }
+}
@@ -0,0 +1,112 @@
+//! A coarse, human-meaningful connection-quality rating derived from a rolling window of
+//! [`ConsumerStat`] and [`ConsumerScore`], so applications can drive a UI indicator or fallback
+//! logic without parsing raw RTC stats themselves.
+
+use crate::consumer::{ConsumerScore, ConsumerStat};
+
+/// Coarse connection-quality rating for a [`crate::consumer::Consumer`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ConnectionQuality {
+    Poor,
+    Low,
+    Medium,
+    High,
+    Perfect,
+}
+
+impl ConnectionQuality {
+    /// Buckets a combined `[0.0, 1.0]` quality value into a rating.
+    fn from_combined(combined: f64) -> Self {
+        if combined >= 0.9 {
+            ConnectionQuality::Perfect
+        } else if combined >= 0.7 {
+            ConnectionQuality::High
+        } else if combined >= 0.5 {
+            ConnectionQuality::Medium
+        } else if combined >= 0.25 {
+            ConnectionQuality::Low
+        } else {
+            ConnectionQuality::Poor
+        }
+    }
+}
+
+/// Consecutive samples a candidate rating must survive before it replaces the reported one, so
+/// transient dips don't flap the rating.
+const HYSTERESIS_SAMPLES: u32 = 3;
+
+/// Tracks a rolling window of stats/score samples and derives a hysteresis-stabilized
+/// [`ConnectionQuality`].
+pub(super) struct ConnectionQualityTracker {
+    last_stat: Option<ConsumerStat>,
+    current: ConnectionQuality,
+    pending: Option<(ConnectionQuality, u32)>,
+}
+
+impl Default for ConnectionQualityTracker {
+    fn default() -> Self {
+        Self {
+            last_stat: None,
+            current: ConnectionQuality::Medium,
+            pending: None,
+        }
+    }
+}
+
+impl ConnectionQualityTracker {
+    /// Feeds a new sample, returning `Some(quality)` only once a candidate rating has held for
+    /// [`HYSTERESIS_SAMPLES`] in a row.
+    pub(super) fn on_sample(
+        &mut self,
+        stat: &ConsumerStat,
+        score: &ConsumerScore,
+    ) -> Option<ConnectionQuality> {
+        let loss_quality = 1.0 - (f64::from(stat.fraction_lost) / 256.0).min(1.0);
+
+        let rtt_quality = match stat.round_trip_time {
+            Some(rtt) => 1.0 - (f64::from(rtt) / 500.0).min(1.0),
+            None => 1.0,
+        };
+
+        let (nack_growth, pli_growth) = match &self.last_stat {
+            Some(last) => (
+                stat.nack_count.saturating_sub(last.nack_count),
+                stat.pli_count.saturating_sub(last.pli_count),
+            ),
+            None => (0, 0),
+        };
+        let growth_quality = 1.0 - ((nack_growth + pli_growth) as f64 / 20.0).min(1.0);
+
+        let score_quality = f64::from(stat.score.max(score.score)) / 10.0;
+
+        let combined = 0.35 * score_quality
+            + 0.25 * loss_quality
+            + 0.2 * rtt_quality
+            + 0.2 * growth_quality;
+
+        self.last_stat = Some(stat.clone());
+
+        let candidate = ConnectionQuality::from_combined(combined);
+
+        if candidate == self.current {
+            self.pending = None;
+            return None;
+        }
+
+        match &mut self.pending {
+            Some((pending_candidate, streak)) if *pending_candidate == candidate => {
+                *streak += 1;
+                if *streak >= HYSTERESIS_SAMPLES {
+                    self.current = candidate;
+                    self.pending = None;
+                    return Some(candidate);
+                }
+            }
+            _ => {
+                self.pending = Some((candidate, 1));
+            }
+        }
+
+        None
+    }
+}
@@ -6,9 +6,15 @@ use crate::messages::{
     ConsumerCloseRequest, ConsumerDumpRequest, ConsumerEnableTraceEventData,
     ConsumerEnableTraceEventRequest, ConsumerGetStatsRequest, ConsumerInternal,
     ConsumerPauseRequest, ConsumerRequestKeyFrameRequest, ConsumerResumeRequest,
-    ConsumerSetPreferredLayersRequest, ConsumerSetPriorityData, ConsumerSetPriorityRequest,
+    ConsumerSetPreferredLayersRequest, ConsumerSetPriorityGroupData,
+    ConsumerSetPriorityGroupRequest,
 };
 use crate::producer::{ProducerId, ProducerStat, ProducerType};
+use crate::router::adaptive_layers::{candidates_from_encodings, AdaptiveLayersConfig, AdaptiveLayersState};
+use crate::router::connection_quality::{ConnectionQuality, ConnectionQualityTracker};
+use crate::router::direct_rtp_stream::{push_with_policy, DroppedPacketCounter, OverflowPolicy};
+use crate::router::signal_strength::{SignalStrength, SignalStrengthTracker};
+use crate::router::stats::{RtcStats, RtcStatsReport};
 use crate::rtp_parameters::{MediaKind, MimeType, RtpCapabilities, RtpParameters};
 use crate::transport::{Transport, TransportGeneric};
 use crate::uuid_based_wrapper_type;
@@ -334,6 +340,8 @@ struct Handlers {
     producer_resume: Bag<Box<dyn Fn() + Send + Sync>>,
     score: Bag<Box<dyn Fn(&ConsumerScore) + Send + Sync>>,
     layers_change: Bag<Box<dyn Fn(&Option<ConsumerLayers>) + Send + Sync>>,
+    connection_quality_change: Bag<Box<dyn Fn(&ConnectionQuality) + Send + Sync>>,
+    signal_strength: Bag<Box<dyn Fn(&SignalStrength) + Send + Sync>>,
     trace: Bag<Box<dyn Fn(&ConsumerTraceEventData) + Send + Sync>>,
     producer_close: BagOnce<Box<dyn FnOnce() + Send>>,
     transport_close: BagOnce<Box<dyn FnOnce() + Send>>,
@@ -354,6 +362,9 @@ struct Inner {
     score: Arc<SyncMutex<ConsumerScore>>,
     preferred_layers: SyncMutex<Option<ConsumerLayers>>,
     current_layers: Arc<SyncMutex<Option<ConsumerLayers>>>,
+    connection_quality: Arc<SyncMutex<ConnectionQuality>>,
+    signal_strength: Arc<SyncMutex<SignalStrength>>,
+    adaptive_layers: Arc<SyncMutex<Option<AdaptiveLayersState>>>,
     handlers: Arc<Handlers>,
     app_data: AppData,
     transport: Arc<Box<dyn Transport>>,
@@ -403,6 +414,22 @@ impl Inner {
     }
 }
 
+/// Builds the per-request tracing span, carrying the [`ConsumerInternal`] fields as structured
+/// key/value pairs so requests can be correlated across a whole router without string-parsing
+/// log lines. Only compiled in when the `tracing` feature is enabled.
+#[cfg(feature = "tracing")]
+fn consumer_request_span(request: &'static str, internal: &ConsumerInternal) -> tracing::Span {
+    tracing::info_span!(
+        "consumer_request",
+        request,
+        router_id = %internal.router_id,
+        transport_id = %internal.transport_id,
+        consumer_id = %internal.consumer_id,
+        producer_id = %internal.producer_id,
+        latency_ms = tracing::field::Empty,
+    )
+}
+
 /// A consumer represents an audio or video source being forwarded from a Mediasoup router to an
 /// endpoint. It's created on top of a transport that defines how the media packets are carried.
 #[derive(Clone)]
@@ -442,6 +469,13 @@ impl Consumer {
         #[allow(clippy::mutex_atomic)]
         let producer_paused = Arc::new(SyncMutex::new(producer_paused));
         let current_layers = Arc::<SyncMutex<Option<ConsumerLayers>>>::default();
+        let connection_quality = Arc::new(SyncMutex::new(ConnectionQuality::Medium));
+        let quality_tracker = Arc::new(SyncMutex::new(ConnectionQualityTracker::default()));
+        let signal_strength = Arc::new(SyncMutex::new(SignalStrength::High));
+        let signal_strength_tracker = Arc::new(SyncMutex::new(SignalStrengthTracker::default()));
+        let adaptive_layers = Arc::<SyncMutex<Option<AdaptiveLayersState>>>::default();
+        let router_id = transport.router_id();
+        let transport_id = transport.id();
 
         let inner_weak = Arc::<SyncMutex<Option<Weak<Inner>>>>::default();
         let subscription_handler = {
@@ -450,6 +484,13 @@ impl Consumer {
             let producer_paused = Arc::clone(&producer_paused);
             let score = Arc::clone(&score);
             let current_layers = Arc::clone(&current_layers);
+            let connection_quality = Arc::clone(&connection_quality);
+            let quality_tracker = Arc::clone(&quality_tracker);
+            let quality_executor = Arc::clone(&executor);
+            let quality_channel = channel.clone();
+            let signal_strength = Arc::clone(&signal_strength);
+            let signal_strength_tracker = Arc::clone(&signal_strength_tracker);
+            let adaptive_layers = Arc::clone(&adaptive_layers);
             let inner_weak = Arc::clone(&inner_weak);
 
             channel
@@ -494,6 +535,104 @@ impl Consumer {
                                 handlers.score.call(|callback| {
                                     callback(&consumer_score);
                                 });
+
+                                let new_signal_strength = signal_strength_tracker
+                                    .lock()
+                                    .on_score(&consumer_score, *current_layers.lock());
+                                if let Some(new_signal_strength) = new_signal_strength {
+                                    *signal_strength.lock() = new_signal_strength;
+                                    handlers.signal_strength.call(|callback| {
+                                        callback(&new_signal_strength);
+                                    });
+                                }
+
+                                let handlers = Arc::clone(&handlers);
+                                let connection_quality = Arc::clone(&connection_quality);
+                                let quality_tracker = Arc::clone(&quality_tracker);
+                                let adaptive_layers = Arc::clone(&adaptive_layers);
+                                let inner_weak = Arc::clone(&inner_weak);
+                                let channel = quality_channel.clone();
+                                let internal = ConsumerInternal {
+                                    router_id,
+                                    transport_id,
+                                    consumer_id: id,
+                                    producer_id,
+                                };
+
+                                quality_executor
+                                    .spawn(async move {
+                                        if let Ok(stats) = channel
+                                            .request(ConsumerGetStatsRequest { internal })
+                                            .await
+                                        {
+                                            let consumer_stat: &ConsumerStat = match &stats {
+                                                ConsumerStats::JustConsumer((consumer_stat,)) => {
+                                                    consumer_stat
+                                                }
+                                                ConsumerStats::WithProducer((
+                                                    consumer_stat,
+                                                    _,
+                                                )) => consumer_stat,
+                                            };
+
+                                            let new_quality = quality_tracker
+                                                .lock()
+                                                .on_sample(consumer_stat, &consumer_score);
+
+                                            if let Some(new_quality) = new_quality {
+                                                *connection_quality.lock() = new_quality;
+                                                handlers.connection_quality_change.call(
+                                                    |callback| {
+                                                        callback(&new_quality);
+                                                    },
+                                                );
+                                            }
+
+                                            // `Consumer`'s own achieved bitrate stands in for a
+                                            // transport-level bandwidth estimate here: the `dyn
+                                            // Transport` handle a consumer holds doesn't expose
+                                            // one, so this is the best headroom signal actually
+                                            // reachable from here.
+                                            let new_layers = adaptive_layers
+                                                .lock()
+                                                .as_mut()
+                                                .and_then(|state| {
+                                                    state.on_report(
+                                                        &consumer_score,
+                                                        consumer_stat.bitrate,
+                                                    )
+                                                });
+
+                                            if let Some(new_layers) = new_layers {
+                                                let internal = ConsumerInternal {
+                                                    router_id,
+                                                    transport_id,
+                                                    consumer_id: id,
+                                                    producer_id,
+                                                };
+
+                                                if let Ok(preferred_layers) = channel
+                                                    .request::<_, Option<ConsumerLayers>>(
+                                                        ConsumerSetPreferredLayersRequest {
+                                                            internal,
+                                                            data: new_layers,
+                                                        },
+                                                    )
+                                                    .await
+                                                {
+                                                    if let Some(inner) = inner_weak
+                                                        .lock()
+                                                        .as_ref()
+                                                        .and_then(Weak::upgrade)
+                                                    {
+                                                        *inner.preferred_layers.lock() =
+                                                            preferred_layers;
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    })
+                                    .detach();
                             }
                             Notification::LayersChange(consumer_layers) => {
                                 *current_layers.lock() = consumer_layers;
@@ -563,6 +702,9 @@ impl Consumer {
             score,
             preferred_layers: SyncMutex::new(preferred_layers),
             current_layers,
+            connection_quality,
+            signal_strength,
+            adaptive_layers,
             executor,
             channel,
             handlers,
@@ -646,6 +788,21 @@ impl Consumer {
         &self.inner.app_data
     }
 
+    /// Coarse connection-quality rating, derived from a rolling window of [`ConsumerStat`] and
+    /// [`ConsumerScore`]. See [`Consumer::on_connection_quality_change`].
+    pub fn connection_quality(&self) -> ConnectionQuality {
+        *self.inner.connection_quality.lock()
+    }
+
+    /// Coarse signal-strength rating derived from an exponential moving average of the
+    /// consumer's score, pulled down while its current layers are the lowest ones or `None`
+    /// (whether due to bandwidth or not). A distinct signal from [`Consumer::connection_quality`]:
+    /// it ignores transport-level stats, looking only at the smoothed score and layers. See
+    /// [`Consumer::on_signal_strength`].
+    pub fn signal_strength(&self) -> SignalStrength {
+        *self.inner.signal_strength.lock()
+    }
+
     /// Whether the consumer is closed.
     pub fn closed(&self) -> bool {
         self.inner.closed.load(Ordering::SeqCst)
@@ -679,17 +836,86 @@ impl Consumer {
             .await
     }
 
+    /// Returns current RTC statistics of the consumer mapped onto the standardized W3C WebRTC
+    /// Statistics dictionaries (as used by the `webrtc` crate's `stats` module), so mediasoup
+    /// numbers can be fed straight into W3C-oriented dashboards and tooling.
+    pub async fn get_stats_standard(&self) -> Result<RtcStatsReport, RequestError> {
+        debug!("get_stats_standard()");
+
+        let stats = self.get_stats().await?;
+
+        let mut report = RtcStatsReport::new();
+
+        let consumer_stat = match &stats {
+            ConsumerStats::JustConsumer((consumer_stat,)) => consumer_stat,
+            ConsumerStats::WithProducer((consumer_stat, _)) => consumer_stat,
+        };
+
+        report.insert(
+            format!("outbound-rtp-{}", consumer_stat.ssrc),
+            RtcStats::OutboundRtp {
+                timestamp: consumer_stat.timestamp as f64,
+                ssrc: consumer_stat.ssrc,
+                kind: format!("{:?}", consumer_stat.kind).to_lowercase(),
+                packets_sent: consumer_stat.packet_count as u64,
+                bytes_sent: consumer_stat.byte_count as u64,
+                nack_count: consumer_stat.nack_count as u64,
+                pli_count: consumer_stat.pli_count as u64,
+                fir_count: consumer_stat.fir_count as u64,
+                retransmitted_packets_sent: consumer_stat.packets_retransmitted as u64,
+                round_trip_time: consumer_stat.round_trip_time.map(|rtt| rtt as f64),
+            },
+        );
+
+        report.insert(
+            format!("remote-inbound-rtp-{}", consumer_stat.ssrc),
+            RtcStats::RemoteInboundRtp {
+                timestamp: consumer_stat.timestamp as f64,
+                ssrc: consumer_stat.ssrc,
+                packets_lost: consumer_stat.packets_lost as i32,
+                fraction_lost: f64::from(consumer_stat.fraction_lost) / 256.0,
+                jitter: 0.0,
+                round_trip_time: consumer_stat.round_trip_time.map(|rtt| rtt as f64),
+            },
+        );
+
+        if let ConsumerStats::WithProducer((_, producer_stat)) = &stats {
+            report.insert(
+                format!("inbound-rtp-{}", producer_stat.ssrc),
+                RtcStats::InboundRtp {
+                    timestamp: producer_stat.timestamp as f64,
+                    ssrc: producer_stat.ssrc,
+                    kind: format!("{:?}", producer_stat.kind).to_lowercase(),
+                    bytes_received: producer_stat.byte_count as u64,
+                    packets_lost: producer_stat.packets_lost as i32,
+                    jitter: f64::from(producer_stat.jitter),
+                },
+            );
+        }
+
+        Ok(report)
+    }
+
     /// Pauses the consumer (no RTP is sent to the consuming endpoint).
     pub async fn pause(&self) -> Result<(), RequestError> {
         debug!("pause()");
 
+        let internal = self.get_internal();
+        #[cfg(feature = "tracing")]
+        let span = consumer_request_span("pause", &internal);
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
         self.inner
             .channel
-            .request(ConsumerPauseRequest {
-                internal: self.get_internal(),
-            })
+            .request(ConsumerPauseRequest { internal })
             .await?;
 
+        #[cfg(feature = "tracing")]
+        span.record("latency_ms", start.elapsed().as_millis());
+
         let mut paused = self.inner.paused.lock();
         let was_paused = *paused || *self.inner.producer_paused.lock();
         *paused = true;
@@ -705,13 +931,22 @@ impl Consumer {
     pub async fn resume(&self) -> Result<(), RequestError> {
         debug!("resume()");
 
+        let internal = self.get_internal();
+        #[cfg(feature = "tracing")]
+        let span = consumer_request_span("resume", &internal);
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
         self.inner
             .channel
-            .request(ConsumerResumeRequest {
-                internal: self.get_internal(),
-            })
+            .request(ConsumerResumeRequest { internal })
             .await?;
 
+        #[cfg(feature = "tracing")]
+        span.record("latency_ms", start.elapsed().as_millis());
+
         let mut paused = self.inner.paused.lock();
         let was_paused = *paused || *self.inner.producer_paused.lock();
         *paused = false;
@@ -731,55 +966,116 @@ impl Consumer {
     ) -> Result<(), RequestError> {
         debug!("set_preferred_layers()");
 
+        let internal = self.get_internal();
+        #[cfg(feature = "tracing")]
+        let span = consumer_request_span("set_preferred_layers", &internal);
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
         let consumer_layers = self
             .inner
             .channel
             .request(ConsumerSetPreferredLayersRequest {
-                internal: self.get_internal(),
+                internal,
                 data: consumer_layers,
             })
             .await?;
 
+        #[cfg(feature = "tracing")]
+        span.record("latency_ms", start.elapsed().as_millis());
+
         *self.inner.preferred_layers.lock() = consumer_layers;
 
         Ok(())
     }
 
+    /// Enables automatic layer selection driven by this consumer's own score and achieved-bitrate
+    /// reports, calling [`Consumer::set_preferred_layers`] on its behalf instead of requiring the
+    /// application to do so manually. See [`crate::router::adaptive_layers::AdaptiveLayersConfig`].
+    ///
+    /// Only meaningful for `Simulcast`/`SVC` consumers. Automatic decisions are surfaced through
+    /// the existing [`Consumer::on_layers_change`] handler, same as manual ones.
+    ///
+    /// Fetches [`Consumer::dump`] once, in the background, to build the candidate layer ladder
+    /// from `consumable_rtp_encodings` before the first `score` report can step it; reports
+    /// arriving before that fetch completes are ignored.
+    pub fn enable_adaptive_layers(&self, config: AdaptiveLayersConfig) {
+        debug!("enable_adaptive_layers()");
+
+        let consumer = self.clone();
+
+        self.inner
+            .executor
+            .spawn(async move {
+                let candidates = match consumer.dump().await {
+                    Ok(dump) => candidates_from_encodings(&dump.consumable_rtp_encodings),
+                    Err(error) => {
+                        error!("enable_adaptive_layers() failed to dump consumer: {}", error);
+                        return;
+                    }
+                };
+
+                *consumer.inner.adaptive_layers.lock() =
+                    Some(AdaptiveLayersState::new(config, candidates, 0));
+            })
+            .detach();
+    }
+
     /// Sets the priority for this consumer. It affects how the estimated outgoing bitrate in the
     /// transport (obtained via transport-cc or REMB) is distributed among all video consumers, by
     /// prioritizing those with higher priority.
+    ///
+    /// This is a thin wrapper around [`Consumer::set_priority_group`] with no parent (a flat
+    /// sibling list at the root of the transport's dependency forest) and `weight == priority`,
+    /// kept for backward compatibility.
     pub async fn set_priority(&self, priority: u8) -> Result<(), RequestError> {
-        debug!("set_preferred_layers()");
-
-        let result = self
-            .inner
-            .channel
-            .request(ConsumerSetPriorityRequest {
-                internal: self.get_internal(),
-                data: ConsumerSetPriorityData { priority },
-            })
-            .await?;
+        debug!("set_priority()");
 
-        *self.inner.priority.lock() = result.priority;
-
-        Ok(())
+        self.set_priority_group(None, priority).await
     }
 
     /// Unsets the priority for this consumer (it sets it to its default value `1`).
     pub async fn unset_priority(&self) -> Result<(), RequestError> {
         debug!("unset_priority()");
 
-        let priority = 1;
+        self.set_priority_group(None, 1).await
+    }
+
+    /// Places this consumer in the transport's weighted priority dependency tree, borrowing
+    /// HTTP/2's stream prioritization model: `weight` is relative to siblings sharing the same
+    /// `parent` (or to other root consumers, if `parent` is `None`). The transport's available
+    /// outgoing bitrate is split among root groups by weight first, then recursed into each
+    /// group's children, so e.g. a screen-share consumer can be made the parent of several camera
+    /// consumers and starve them only when bandwidth is tight.
+    pub async fn set_priority_group(
+        &self,
+        parent: Option<ConsumerId>,
+        weight: u8,
+    ) -> Result<(), RequestError> {
+        debug!("set_priority_group()");
+
+        let internal = self.get_internal();
+        #[cfg(feature = "tracing")]
+        let span = consumer_request_span("set_priority_group", &internal);
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
 
         let result = self
             .inner
             .channel
-            .request(ConsumerSetPriorityRequest {
-                internal: self.get_internal(),
-                data: ConsumerSetPriorityData { priority },
+            .request(ConsumerSetPriorityGroupRequest {
+                internal,
+                data: ConsumerSetPriorityGroupData { parent, weight },
             })
             .await?;
 
+        #[cfg(feature = "tracing")]
+        span.record("latency_ms", start.elapsed().as_millis());
+
         *self.inner.priority.lock() = result.priority;
 
         Ok(())
@@ -789,12 +1085,24 @@ impl Consumer {
     pub async fn request_key_frame(&self) -> Result<(), RequestError> {
         debug!("request_key_frame()");
 
-        self.inner
+        let internal = self.get_internal();
+        #[cfg(feature = "tracing")]
+        let span = consumer_request_span("request_key_frame", &internal);
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
+        let result = self
+            .inner
             .channel
-            .request(ConsumerRequestKeyFrameRequest {
-                internal: self.get_internal(),
-            })
-            .await
+            .request(ConsumerRequestKeyFrameRequest { internal })
+            .await;
+
+        #[cfg(feature = "tracing")]
+        span.record("latency_ms", start.elapsed().as_millis());
+
+        result
     }
 
     /// Instructs the consumer to emit "trace" events. For monitoring purposes. Use with caution.
@@ -804,13 +1112,27 @@ impl Consumer {
     ) -> Result<(), RequestError> {
         debug!("enable_trace_event()");
 
-        self.inner
+        let internal = self.get_internal();
+        #[cfg(feature = "tracing")]
+        let span = consumer_request_span("enable_trace_event", &internal);
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
+        let result = self
+            .inner
             .channel
             .request(ConsumerEnableTraceEventRequest {
-                internal: self.get_internal(),
+                internal,
                 data: ConsumerEnableTraceEventData { types },
             })
-            .await
+            .await;
+
+        #[cfg(feature = "tracing")]
+        span.record("latency_ms", start.elapsed().as_millis());
+
+        result
     }
 
     /// Callback is called when the consumer receives through its router a RTP packet from the
@@ -823,6 +1145,41 @@ impl Consumer {
         self.inner.handlers.rtp.add(Box::new(callback))
     }
 
+    /// Same as [`Consumer::on_rtp`], but packets are placed into a bounded queue with
+    /// `overflow_policy` first, so a `callback` that is slower than the producer's line rate
+    /// applies backpressure or drops packets instead of retaining them unboundedly. Returns the
+    /// subscription's [`HandlerId`] alongside a [`DroppedPacketCounter`] tracking how many
+    /// packets `overflow_policy` has discarded.
+    pub fn on_rtp_with_policy<F: Fn(&Bytes) + Send + Sync + 'static>(
+        &self,
+        capacity: usize,
+        overflow_policy: OverflowPolicy,
+        callback: F,
+    ) -> (HandlerId, DroppedPacketCounter) {
+        let (sender, receiver) = async_channel::bounded(capacity.max(1));
+        let dropped = DroppedPacketCounter::default();
+
+        let handler_id = {
+            let sender = sender.clone();
+            let dropped = dropped.clone();
+
+            self.on_rtp(move |packet: &Bytes| {
+                push_with_policy(&sender, &dropped, overflow_policy, packet.clone());
+            })
+        };
+
+        self.inner
+            .executor
+            .spawn(async move {
+                while let Ok(packet) = receiver.recv().await {
+                    callback(&packet);
+                }
+            })
+            .detach();
+
+        (handler_id, dropped)
+    }
+
     /// Callback is called when the consumer or its associated producer is paused and, as result,
     /// the consumer becomes paused.
     pub fn on_pause<F: Fn() + Send + Sync + 'static>(&self, callback: F) -> HandlerId {
@@ -877,6 +1234,28 @@ impl Consumer {
         self.inner.handlers.layers_change.add(Box::new(callback))
     }
 
+    /// Callback is called when the derived [`ConnectionQuality`] rating changes. Only fires when
+    /// the bucket actually changes, so transient dips in the underlying stats don't flap it.
+    pub fn on_connection_quality_change<F: Fn(&ConnectionQuality) + Send + Sync + 'static>(
+        &self,
+        callback: F,
+    ) -> HandlerId {
+        self.inner
+            .handlers
+            .connection_quality_change
+            .add(Box::new(callback))
+    }
+
+    /// Callback is called when the derived [`SignalStrength`] rating changes. Only fires once a
+    /// candidate rating has held steady across several score updates, so the signal does not
+    /// flap on every noisy tick.
+    pub fn on_signal_strength<F: Fn(&SignalStrength) + Send + Sync + 'static>(
+        &self,
+        callback: F,
+    ) -> HandlerId {
+        self.inner.handlers.signal_strength.add(Box::new(callback))
+    }
+
     /// See [`Consumer::enable_trace_event`] method.
     pub fn on_trace<F: Fn(&ConsumerTraceEventData) + Send + Sync + 'static>(
         &self,
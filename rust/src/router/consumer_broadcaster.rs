@@ -0,0 +1,125 @@
+//! A `tee`-like fan-out abstraction: wraps a single producer and manages a dynamic set of
+//! consumers created across many transports, so attaching/detaching a consuming endpoint at
+//! runtime is a single call instead of hand-wiring `transport.consume()` plus every lifecycle
+//! handler by hand.
+
+use crate::consumer::{Consumer, ConsumerId, ConsumerOptions};
+use crate::producer::{Producer, ProducerId};
+use crate::rtp_parameters::RtpCapabilities;
+use crate::transport::Transport;
+use crate::worker::RequestError;
+use parking_lot::Mutex as SyncMutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+
+struct Inner {
+    producer: Producer,
+    consumers: SyncMutex<HashMap<ConsumerId, Consumer>>,
+    producer_paused: AtomicBool,
+}
+
+/// Fans a single producer out to a dynamically managed set of consumers, each of which may live
+/// on a different transport. A consumer leaves the set when it is taken out with
+/// [`ConsumerBroadcaster::remove_consumer`], and removes itself the moment it closes because its
+/// producer or its owning transport closed. The broadcaster also tracks its producer's own pause state and
+/// clears the whole set once the producer closes, since a closed producer has nothing left to
+/// broadcast.
+#[derive(Clone)]
+pub struct ConsumerBroadcaster {
+    inner: Arc<Inner>,
+}
+
+impl ConsumerBroadcaster {
+    /// Creates a broadcaster fanning out the given producer.
+    pub fn new(producer: Producer) -> Self {
+        let inner = Arc::new(Inner {
+            producer_paused: AtomicBool::new(false),
+            producer,
+            consumers: SyncMutex::new(HashMap::new()),
+        });
+
+        let pause_inner = Arc::clone(&inner);
+        inner.producer.on_pause(move || {
+            pause_inner.producer_paused.store(true, Ordering::SeqCst);
+        });
+
+        let resume_inner = Arc::clone(&inner);
+        inner.producer.on_resume(move || {
+            resume_inner.producer_paused.store(false, Ordering::SeqCst);
+        });
+
+        let close_inner = Arc::clone(&inner);
+        inner.producer.on_close(move || {
+            close_inner.consumers.lock().clear();
+        });
+
+        Self { inner }
+    }
+
+    /// Producer id this broadcaster fans out.
+    pub fn producer_id(&self) -> ProducerId {
+        self.inner.producer.id()
+    }
+
+    /// Whether the producer this broadcaster fans out is currently paused.
+    pub fn producer_paused(&self) -> bool {
+        self.inner.producer_paused.load(Ordering::SeqCst)
+    }
+
+    /// Creates a consumer for this producer on `transport` and adds it to the broadcast set.
+    pub async fn add_consumer<Dump, Stat, RemoteParameters, T>(
+        &self,
+        transport: &T,
+        rtp_capabilities: RtpCapabilities,
+    ) -> Result<Consumer, RequestError>
+    where
+        T: Transport<Dump, Stat, RemoteParameters>,
+    {
+        let consumer = transport
+            .consume(ConsumerOptions::new(
+                self.inner.producer.id(),
+                rtp_capabilities,
+            ))
+            .await?;
+
+        let consumer_id = consumer.id();
+
+        self.inner
+            .consumers
+            .lock()
+            .insert(consumer_id, consumer.clone());
+
+        let inner_weak = Arc::downgrade(&self.inner);
+        consumer.on_close(move || {
+            if let Some(inner) = Weak::upgrade(&inner_weak) {
+                inner.consumers.lock().remove(&consumer_id);
+            }
+        });
+
+        Ok(consumer)
+    }
+
+    /// Removes the consumer with the given id from the broadcast set and returns it. The consumer
+    /// is not closed here: it closes (its own `Drop` issues the worker close request) once every
+    /// clone of it, including the returned one and the one [`Self::add_consumer`] handed out, is
+    /// dropped.
+    pub fn remove_consumer(&self, consumer_id: ConsumerId) -> Option<Consumer> {
+        self.inner.consumers.lock().remove(&consumer_id)
+    }
+
+    /// Live consumers currently in the broadcast set.
+    pub fn consumers(&self) -> Vec<Consumer> {
+        self.inner.consumers.lock().values().cloned().collect()
+    }
+
+    /// Number of live consumers currently in the broadcast set.
+    pub fn len(&self) -> usize {
+        self.inner.consumers.lock().len()
+    }
+
+    /// Whether the broadcast set is currently empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.consumers.lock().is_empty()
+    }
+}
@@ -1,16 +1,19 @@
 use crate::data_producer::DataProducerId;
-use crate::data_structures::AppData;
+use crate::data_structures::{AppData, WebRtcMessage};
 use crate::event_handlers::{Bag, HandlerId};
 use crate::messages::{
     DataConsumerCloseRequest, DataConsumerDumpRequest, DataConsumerGetBufferedAmountRequest,
-    DataConsumerGetStatsRequest, DataConsumerInternal,
-    DataConsumerSetBufferedAmountLowThresholdData,
+    DataConsumerGetStatsRequest, DataConsumerInternal, DataConsumerSendData,
+    DataConsumerSendNotification, DataConsumerSetBufferedAmountLowThresholdData,
     DataConsumerSetBufferedAmountLowThresholdRequest,
 };
 use crate::sctp_parameters::SctpStreamParameters;
 use crate::transport::Transport;
 use crate::uuid_based_wrapper_type;
-use crate::worker::{Channel, RequestError, SubscriptionHandler};
+use crate::worker::{
+    Channel, NotificationError, NotificationMessage, PayloadChannel, RequestError,
+    SubscriptionHandler,
+};
 use async_executor::Executor;
 use log::*;
 use serde::{Deserialize, Serialize};
@@ -147,8 +150,17 @@ enum Notification {
     BufferedAmountLow,
 }
 
+// Carried over the payload channel rather than the regular one, since each variant is paired with
+// a binary payload (see `DataConsumer::new`'s payload channel subscription).
+#[derive(Debug, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase", content = "data")]
+enum PayloadNotification {
+    Message { ppid: u32 },
+}
+
 #[derive(Default)]
 struct Handlers {
+    message: Bag<dyn Fn(&[u8], u32) + Send>,
     sctp_send_buffer_full: Bag<dyn Fn() + Send>,
     buffered_amount_low: Bag<dyn Fn() + Send>,
     closed: Bag<dyn FnOnce() + Send>,
@@ -163,12 +175,12 @@ struct Inner {
     data_producer_id: DataProducerId,
     executor: Arc<Executor<'static>>,
     channel: Channel,
-    payload_channel: Channel,
+    payload_channel: PayloadChannel,
     handlers: Arc<Handlers>,
     app_data: AppData,
     transport: Box<dyn Transport>,
-    // Drop subscription to consumer-specific notifications when consumer itself is dropped
-    _subscription_handler: SubscriptionHandler,
+    // Drop subscriptions to consumer-specific notifications when consumer itself is dropped
+    _subscription_handlers: Vec<SubscriptionHandler>,
 }
 
 impl Drop for Inner {
@@ -213,7 +225,7 @@ impl DataConsumer {
         data_producer_id: DataProducerId,
         executor: Arc<Executor<'static>>,
         channel: Channel,
-        payload_channel: Channel,
+        payload_channel: PayloadChannel,
         app_data: AppData,
         transport: Box<dyn Transport>,
     ) -> Self {
@@ -246,7 +258,34 @@ impl DataConsumer {
                 .await
                 .unwrap()
         };
-        // TODO: payload_channel subscription for direct transport
+
+        let payload_subscription_handler = {
+            let handlers = Arc::clone(&handlers);
+
+            payload_channel
+                .subscribe_to_notifications(id.to_string(), move |notification| {
+                    let NotificationMessage { message, payload } = notification;
+                    match serde_json::from_value::<PayloadNotification>(message) {
+                        Ok(PayloadNotification::Message { ppid }) => {
+                            let message = WebRtcMessage::from_ppid_and_payload(ppid, payload);
+                            let data: &[u8] = match &message {
+                                WebRtcMessage::String(string) => string.as_bytes(),
+                                WebRtcMessage::Binary(binary) => binary.as_slice(),
+                                WebRtcMessage::EmptyString | WebRtcMessage::EmptyBinary => &[],
+                            };
+
+                            handlers.message.call(|callback| {
+                                callback(data, ppid);
+                            });
+                        }
+                        Err(error) => {
+                            error!("Failed to parse payload notification: {}", error);
+                        }
+                    }
+                })
+                .await
+                .unwrap()
+        };
 
         let inner = Arc::new(Inner {
             id,
@@ -261,7 +300,7 @@ impl DataConsumer {
             handlers,
             app_data,
             transport,
-            _subscription_handler: subscription_handler,
+            _subscription_handlers: vec![subscription_handler, payload_subscription_handler],
         });
 
         Self { inner }
@@ -361,51 +400,33 @@ impl DataConsumer {
             .await
     }
 
-    // TODO: Not sure what is the purpose of this: https://github.com/versatica/mediasoup/pull/444
-    // /**
-    //  * Send data.
-    //  */
-    // async send(message: string | Buffer, ppid?: number): Promise<void>
-    // {
-    // 	if (typeof message !== 'string' && !Buffer.isBuffer(message))
-    // 	{
-    // 		throw new TypeError('message must be a string or a Buffer');
-    // 	}
-    //
-    // 	/*
-    // 	 * +-------------------------------+----------+
-    // 	 * | Value                         | SCTP     |
-    // 	 * |                               | PPID     |
-    // 	 * +-------------------------------+----------+
-    // 	 * | WebRTC String                 | 51       |
-    // 	 * | WebRTC Binary Partial         | 52       |
-    // 	 * | (Deprecated)                  |          |
-    // 	 * | WebRTC Binary                 | 53       |
-    // 	 * | WebRTC String Partial         | 54       |
-    // 	 * | (Deprecated)                  |          |
-    // 	 * | WebRTC String Empty           | 56       |
-    // 	 * | WebRTC Binary Empty           | 57       |
-    // 	 * +-------------------------------+----------+
-    // 	 */
-    //
-    // 	if (typeof ppid !== 'number')
-    // 	{
-    // 		ppid = (typeof message === 'string')
-    // 			? message.length > 0 ? 51 : 56
-    // 			: message.length > 0 ? 53 : 57;
-    // 	}
-    //
-    // 	// Ensure we honor PPIDs.
-    // 	if (ppid === 56)
-    // 		message = ' ';
-    // 	else if (ppid === 57)
-    // 		message = Buffer.alloc(1);
-    //
-    // 	const requestData = { ppid };
-    //
-    // 	await this._payloadChannel.request(
-    // 		'dataConsumer.send', this._internal, requestData, message);
-    // }
+    /// Sends data over this `Direct`-type DataConsumer's payload channel, e.g. to echo data back
+    /// to the producing endpoint. [`WebRtcMessage`] takes care of the SCTP PPID bookkeeping (see
+    /// [`DataConsumer::on_message`]) so callers never have to pick a PPID themselves.
+    pub async fn send(&self, message: WebRtcMessage) -> Result<(), NotificationError> {
+        debug!("send()");
+
+        let (ppid, payload) = message.into_ppid_and_payload();
+
+        self.inner
+            .payload_channel
+            .notify(
+                DataConsumerSendNotification {
+                    internal: self.get_internal(),
+                    data: DataConsumerSendData { ppid },
+                },
+                payload,
+            )
+            .await
+    }
+
+    /// Callback is called for each message received over this `Direct`-type DataConsumer's
+    /// payload channel, with the decoded payload (empty for [`WebRtcMessage::EmptyString`]/
+    /// [`WebRtcMessage::EmptyBinary`], their placeholder byte stripped) and the raw SCTP PPID it
+    /// arrived with.
+    pub fn on_message<F: Fn(&[u8], u32) + Send + 'static>(&self, callback: F) -> HandlerId {
+        self.inner.handlers.message.add(Box::new(callback))
+    }
 
     pub fn on_sctp_send_buffer_full<F: Fn() + Send + 'static>(&self, callback: F) -> HandlerId {
         self.inner
@@ -1,24 +1,41 @@
-use crate::data_structures::{AppData, WebRtcMessage};
+use crate::data_structures::{AppData, EventDirection, WebRtcMessage};
 use crate::messages::{
-    DataProducerCloseRequest, DataProducerDumpRequest, DataProducerGetStatsRequest,
-    DataProducerInternal, DataProducerSendData, DataProducerSendNotification,
+    DataProducerCloseRequest, DataProducerDumpRequest, DataProducerEnableTraceEventData,
+    DataProducerEnableTraceEventRequest, DataProducerGetStatsRequest, DataProducerInternal,
+    DataProducerPauseRequest, DataProducerResumeRequest, DataProducerSendData,
+    DataProducerSendNotification,
 };
 use crate::sctp_parameters::SctpStreamParameters;
 use crate::transport::{Transport, TransportGeneric};
 use crate::uuid_based_wrapper_type;
-use crate::worker::{Channel, NotificationError, PayloadChannel, RequestError};
+use crate::worker::channels::RequestPriority;
+use crate::worker::{
+    Channel, NotificationError, PayloadChannel, RequestError, SubscriptionHandler,
+};
 use async_executor::Executor;
-use event_listener_primitives::{BagOnce, HandlerId};
+use event_listener_primitives::{Bag, BagOnce, HandlerId};
 use log::*;
 use parking_lot::Mutex as SyncMutex;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use thiserror::Error;
 
 uuid_based_wrapper_type!(DataProducerId);
 
+/// Default [`DataProducerOptions::buffered_amount_low_threshold`], matching the W3C
+/// `RTCDataChannel.bufferedAmountLowThreshold` default of `0`.
+const DEFAULT_BUFFERED_AMOUNT_LOW_THRESHOLD: u32 = 0;
+
+/// Default [`DataProducerOptions::buffered_amount_high_water_mark`]: 1 MiB of unacknowledged
+/// data in flight before [`DirectDataProducer::send`] starts rejecting new messages.
+const DEFAULT_BUFFERED_AMOUNT_HIGH_WATER_MARK: u32 = 1024 * 1024;
+
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub struct DataProducerOptions {
@@ -35,6 +52,23 @@ pub struct DataProducerOptions {
     pub protocol: String,
     /// Custom application data.
     pub app_data: AppData,
+    /// Buffered amount threshold (in bytes) below which [`DataProducer::on_buffered_amount_low`]
+    /// fires, once it has crossed above it.
+    pub buffered_amount_low_threshold: u32,
+    /// Buffered amount (in bytes) above which [`DirectDataProducer::send`] rejects new messages
+    /// with [`SendError::TooMuchBufferedAmount`], instead of growing the worker's outgoing queue
+    /// unboundedly.
+    pub buffered_amount_high_water_mark: u32,
+    /// When set, spawns a background task on the executor that polls [`DataProducer::get_stats`]
+    /// at this interval and emits it through [`DataProducer::on_stats`] along with the computed
+    /// messages/sec and bytes/sec deltas since the previous sample.
+    pub stats_poll_interval: Option<Duration>,
+    /// Known subprotocol names and versions the application supports. The endpoint-declared
+    /// `protocol` (`name` or `name@major.minor`) is checked against this registry when the
+    /// DataProducer is created: an unknown name or a major-version mismatch is rejected with
+    /// [`DataProducerError::UnsupportedProtocol`]. Left empty (the default), validation is
+    /// skipped and `protocol` is accepted as-is, preserving the previous free-form behavior.
+    pub subprotocol_registry: Vec<SubprotocolDescriptor>,
 }
 
 impl DataProducerOptions {
@@ -48,6 +82,10 @@ impl DataProducerOptions {
             label: "".to_string(),
             protocol: "".to_string(),
             app_data: AppData::default(),
+            buffered_amount_low_threshold: DEFAULT_BUFFERED_AMOUNT_LOW_THRESHOLD,
+            buffered_amount_high_water_mark: DEFAULT_BUFFERED_AMOUNT_HIGH_WATER_MARK,
+            stats_poll_interval: None,
+            subprotocol_registry: Vec::new(),
         }
     }
 
@@ -58,6 +96,10 @@ impl DataProducerOptions {
             label: "".to_string(),
             protocol: "".to_string(),
             app_data: AppData::default(),
+            buffered_amount_low_threshold: DEFAULT_BUFFERED_AMOUNT_LOW_THRESHOLD,
+            buffered_amount_high_water_mark: DEFAULT_BUFFERED_AMOUNT_HIGH_WATER_MARK,
+            stats_poll_interval: None,
+            subprotocol_registry: Vec::new(),
         }
     }
 
@@ -69,8 +111,80 @@ impl DataProducerOptions {
             label: "".to_string(),
             protocol: "".to_string(),
             app_data: AppData::default(),
+            buffered_amount_low_threshold: DEFAULT_BUFFERED_AMOUNT_LOW_THRESHOLD,
+            buffered_amount_high_water_mark: DEFAULT_BUFFERED_AMOUNT_HIGH_WATER_MARK,
+            stats_poll_interval: None,
+            subprotocol_registry: Vec::new(),
+        }
+    }
+}
+
+/// A semantic `major.minor` version, as declared in a [`SubprotocolDescriptor`] or parsed from an
+/// endpoint's `protocol` string.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SubprotocolVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+/// An entry in [`DataProducerOptions::subprotocol_registry`]: a subprotocol name the application
+/// knows how to handle, together with the version it implements.
+#[derive(Debug, Clone)]
+pub struct SubprotocolDescriptor {
+    pub name: String,
+    pub version: SubprotocolVersion,
+}
+
+/// Parses `protocol` as `name` or `name@major.minor` and checks it against `registry`, returning
+/// the negotiated version. An empty registry skips validation entirely.
+fn negotiate_subprotocol_version(
+    protocol: &str,
+    registry: &[SubprotocolDescriptor],
+) -> Result<Option<SubprotocolVersion>, DataProducerError> {
+    if registry.is_empty() {
+        return Ok(None);
+    }
+
+    let (name, declared_version) = match protocol.split_once('@') {
+        Some((name, version)) => {
+            let parsed = version.split_once('.').and_then(|(major, minor)| {
+                Some(SubprotocolVersion {
+                    major: major.parse().ok()?,
+                    minor: minor.parse().ok()?,
+                })
+            });
+            let parsed = parsed.ok_or_else(|| DataProducerError::UnsupportedProtocol {
+                protocol: protocol.to_string(),
+            })?;
+            (name, parsed)
         }
+        None => (protocol, SubprotocolVersion { major: 0, minor: 0 }),
+    };
+
+    let registered = registry
+        .iter()
+        .find(|descriptor| descriptor.name == name)
+        .ok_or_else(|| DataProducerError::UnsupportedProtocol {
+            protocol: protocol.to_string(),
+        })?;
+
+    if registered.version.major != declared_version.major {
+        return Err(DataProducerError::UnsupportedProtocol {
+            protocol: protocol.to_string(),
+        });
     }
+
+    Ok(Some(declared_version))
+}
+
+/// Error produced while validating [`DataProducerOptions::protocol`] against
+/// [`DataProducerOptions::subprotocol_registry`] at creation time.
+#[derive(Debug, Error)]
+pub enum DataProducerError {
+    /// `protocol` names a subprotocol the registry doesn't know about, or declares a version
+    /// whose major component doesn't match the registered one.
+    #[error("unsupported subprotocol `{protocol}`")]
+    UnsupportedProtocol { protocol: String },
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
@@ -90,6 +204,8 @@ pub struct DataProducerDump {
     pub label: String,
     pub protocol: String,
     pub sctp_stream_parameters: Option<SctpStreamParameters>,
+    pub paused: bool,
+    pub trace_event_types: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -104,8 +220,59 @@ pub struct DataProducerStat {
     pub bytes_received: usize,
 }
 
+/// Throughput deltas computed between two consecutive [`DataProducerStat`] samples collected by
+/// the background poller enabled via [`DataProducerOptions::stats_poll_interval`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct DataProducerStatsDelta {
+    pub messages_per_second: f64,
+    pub bytes_per_second: f64,
+}
+
+/// 'trace' event data.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DataProducerTraceEventData {
+    Message {
+        /// Event timestamp.
+        timestamp: u64,
+        /// Event direction.
+        direction: EventDirection,
+        /// PPID of the received SCTP message.
+        ppid: u32,
+        /// Size in bytes of the received SCTP message.
+        size: usize,
+    },
+}
+
+/// Types of data producer trace events.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataProducerTraceEventType {
+    /// SCTP message received.
+    Message,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase", content = "data")]
+enum Notification {
+    BufferedAmount(BufferedAmountNotification),
+    Trace(DataProducerTraceEventData),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferedAmountNotification {
+    buffered_amount: u32,
+}
+
 #[derive(Default)]
 struct Handlers {
+    buffered_amount_low: Bag<Box<dyn Fn() + Send + Sync>>,
+    stats: Bag<Box<dyn Fn(&DataProducerStat, &DataProducerStatsDelta) + Send + Sync>>,
+    pause: Bag<Box<dyn Fn() + Send + Sync>>,
+    resume: Bag<Box<dyn Fn() + Send + Sync>>,
+    trace: Bag<Box<dyn Fn(&DataProducerTraceEventData) + Send + Sync>>,
     transport_close: BagOnce<Box<dyn FnOnce() + Send>>,
     close: BagOnce<Box<dyn FnOnce() + Send>>,
 }
@@ -123,6 +290,20 @@ struct Inner {
     app_data: AppData,
     transport: Arc<Box<dyn Transport>>,
     closed: AtomicBool,
+    paused: Arc<SyncMutex<bool>>,
+    buffered_amount: Arc<AtomicU32>,
+    buffered_amount_high_water_mark: u32,
+    // Number of `send()` notifications dispatched by the `Sink` implementation that haven't
+    // completed yet; `poll_flush`/`poll_close` wait for this to reach zero.
+    outstanding_sends: Arc<AtomicUsize>,
+    // Woken once `buffered_amount` drops to or below `buffered_amount_high_water_mark`.
+    ready_waker: Arc<SyncMutex<Option<Waker>>>,
+    // Woken once `outstanding_sends` reaches zero.
+    flush_waker: Arc<SyncMutex<Option<Waker>>>,
+    // Last sample taken by the `stats_poll_interval` background poller, used to compute deltas.
+    last_stat: SyncMutex<Option<DataProducerStat>>,
+    negotiated_subprotocol_version: Option<SubprotocolVersion>,
+    _subscription_handler: SubscriptionHandler,
     _on_transport_close_handler: SyncMutex<HandlerId>,
 }
 
@@ -202,13 +383,17 @@ impl DataProducer {
         sctp_stream_parameters: Option<SctpStreamParameters>,
         label: String,
         protocol: String,
+        buffered_amount_low_threshold: u32,
+        buffered_amount_high_water_mark: u32,
+        stats_poll_interval: Option<Duration>,
+        subprotocol_registry: Vec<SubprotocolDescriptor>,
         executor: Arc<Executor<'static>>,
         channel: Channel,
         payload_channel: PayloadChannel,
         app_data: AppData,
         transport: Transport,
         direct: bool,
-    ) -> Self
+    ) -> Result<Self, DataProducerError>
     where
         Dump: Debug + DeserializeOwned + 'static,
         Stat: Debug + DeserializeOwned + 'static,
@@ -216,7 +401,55 @@ impl DataProducer {
     {
         debug!("new()");
 
+        let negotiated_subprotocol_version =
+            negotiate_subprotocol_version(&protocol, &subprotocol_registry)?;
+
         let handlers = Arc::<Handlers>::default();
+        let paused = Arc::new(SyncMutex::new(false));
+        let buffered_amount = Arc::new(AtomicU32::new(0));
+        let outstanding_sends = Arc::new(AtomicUsize::new(0));
+        let ready_waker = Arc::<SyncMutex<Option<Waker>>>::default();
+        let flush_waker = Arc::<SyncMutex<Option<Waker>>>::default();
+
+        let subscription_handler = {
+            let handlers = Arc::clone(&handlers);
+            let buffered_amount = Arc::clone(&buffered_amount);
+            let ready_waker = Arc::clone(&ready_waker);
+
+            channel
+                .subscribe_to_notifications(id.to_string(), move |notification| {
+                    match serde_json::from_value::<Notification>(notification) {
+                        Ok(Notification::BufferedAmount(BufferedAmountNotification {
+                            buffered_amount: new_buffered_amount,
+                        })) => {
+                            let was_above_threshold =
+                                buffered_amount.load(Ordering::SeqCst) > buffered_amount_low_threshold;
+                            buffered_amount.store(new_buffered_amount, Ordering::SeqCst);
+
+                            if was_above_threshold
+                                && new_buffered_amount <= buffered_amount_low_threshold
+                            {
+                                handlers.buffered_amount_low.call_simple();
+                            }
+
+                            if new_buffered_amount <= buffered_amount_high_water_mark {
+                                if let Some(waker) = ready_waker.lock().take() {
+                                    waker.wake();
+                                }
+                            }
+                        }
+                        Ok(Notification::Trace(trace_event_data)) => {
+                            handlers.trace.call(|callback| {
+                                callback(&trace_event_data);
+                            });
+                        }
+                        Err(error) => {
+                            error!("Failed to parse notification: {}", error);
+                        }
+                    }
+                })
+                .await
+        };
 
         let inner_weak = Arc::<SyncMutex<Option<Weak<Inner>>>>::default();
         let on_transport_close_handler = transport.on_close({
@@ -233,6 +466,8 @@ impl DataProducer {
                 }
             }
         });
+        let stats_poll_executor = Arc::clone(&executor);
+
         let inner = Arc::new(Inner {
             id,
             r#type,
@@ -246,16 +481,100 @@ impl DataProducer {
             app_data,
             transport: Arc::new(Box::new(transport)),
             closed: AtomicBool::new(false),
+            paused,
+            buffered_amount,
+            buffered_amount_high_water_mark,
+            outstanding_sends,
+            ready_waker,
+            flush_waker,
+            last_stat: SyncMutex::new(None),
+            negotiated_subprotocol_version,
+            _subscription_handler: subscription_handler,
             _on_transport_close_handler: SyncMutex::new(on_transport_close_handler),
         });
 
         inner_weak.lock().replace(Arc::downgrade(&inner));
 
-        if direct {
+        if let Some(interval) = stats_poll_interval {
+            let inner_weak = Arc::clone(&inner_weak);
+
+            stats_poll_executor
+                .spawn(async move {
+                    loop {
+                        async_io::Timer::after(interval).await;
+
+                        let Some(inner) = inner_weak
+                            .lock()
+                            .as_ref()
+                            .and_then(|weak_inner| weak_inner.upgrade())
+                        else {
+                            break;
+                        };
+
+                        if inner.closed.load(Ordering::SeqCst) {
+                            break;
+                        }
+
+                        let internal = DataProducerInternal {
+                            router_id: inner.transport.router_id(),
+                            transport_id: inner.transport.id(),
+                            data_producer_id: inner.id,
+                        };
+
+                        let stats = match inner
+                            .channel
+                            .request(DataProducerGetStatsRequest { internal })
+                            .await
+                        {
+                            Ok(stats) => stats,
+                            Err(error) => {
+                                error!("stats poll failed: {}", error);
+                                continue;
+                            }
+                        };
+
+                        let Some(stat) = stats.into_iter().next() else {
+                            continue;
+                        };
+
+                        let previous_stat = inner.last_stat.lock().replace(stat.clone());
+
+                        let delta = previous_stat.map_or(
+                            DataProducerStatsDelta {
+                                messages_per_second: 0.0,
+                                bytes_per_second: 0.0,
+                            },
+                            |previous_stat| {
+                                let seconds = interval.as_secs_f64();
+
+                                DataProducerStatsDelta {
+                                    messages_per_second: stat
+                                        .messages_received
+                                        .saturating_sub(previous_stat.messages_received)
+                                        as f64
+                                        / seconds,
+                                    bytes_per_second: stat
+                                        .bytes_received
+                                        .saturating_sub(previous_stat.bytes_received)
+                                        as f64
+                                        / seconds,
+                                }
+                            },
+                        );
+
+                        inner.handlers.stats.call(|callback| {
+                            callback(&stat, &delta);
+                        });
+                    }
+                })
+                .detach();
+        }
+
+        Ok(if direct {
             Self::Direct(DirectDataProducer { inner })
         } else {
             Self::Regular(RegularDataProducer { inner })
-        }
+        })
     }
 
     /// DataProducer id.
@@ -283,15 +602,33 @@ impl DataProducer {
         &self.inner().protocol
     }
 
+    /// Version negotiated against [`DataProducerOptions::subprotocol_registry`] for
+    /// [`DataProducer::protocol`], or `None` if the registry was empty and validation was
+    /// skipped.
+    pub fn negotiated_subprotocol_version(&self) -> Option<SubprotocolVersion> {
+        self.inner().negotiated_subprotocol_version
+    }
+
     /// App custom data.
     pub fn app_data(&self) -> &AppData {
         &self.inner().app_data
     }
 
+    /// Bytes currently buffered and not yet acknowledged by the worker. Only meaningful for
+    /// [`DirectDataProducer::send`]; regular (SCTP) data producers don't track it.
+    pub fn buffered_amount(&self) -> u32 {
+        self.inner().buffered_amount.load(Ordering::SeqCst)
+    }
+
     pub fn closed(&self) -> bool {
         self.inner().closed.load(Ordering::SeqCst)
     }
 
+    /// Whether the data producer is paused (no received messages are forwarded while paused).
+    pub fn paused(&self) -> bool {
+        *self.inner().paused.lock()
+    }
+
     /// Dump DataProducer.
     #[doc(hidden)]
     pub async fn dump(&self) -> Result<DataProducerDump, RequestError> {
@@ -317,6 +654,108 @@ impl DataProducer {
             .await
     }
 
+    /// Pauses the data producer (received messages are dropped by the worker instead of being
+    /// forwarded).
+    pub async fn pause(&self) -> Result<(), RequestError> {
+        debug!("pause()");
+
+        self.inner()
+            .channel
+            .request(DataProducerPauseRequest {
+                internal: self.get_internal(),
+            })
+            .await?;
+
+        let mut paused = self.inner().paused.lock();
+        let was_paused = *paused;
+        *paused = true;
+
+        if !was_paused {
+            self.inner().handlers.pause.call_simple();
+        }
+
+        Ok(())
+    }
+
+    /// Resumes the data producer (received messages are forwarded again).
+    pub async fn resume(&self) -> Result<(), RequestError> {
+        debug!("resume()");
+
+        self.inner()
+            .channel
+            .request(DataProducerResumeRequest {
+                internal: self.get_internal(),
+            })
+            .await?;
+
+        let mut paused = self.inner().paused.lock();
+        let was_paused = *paused;
+        *paused = false;
+
+        if was_paused {
+            self.inner().handlers.resume.call_simple();
+        }
+
+        Ok(())
+    }
+
+    /// Instructs the data producer to emit "trace" events. For monitoring purposes. Use with
+    /// caution.
+    pub async fn enable_trace_event(
+        &self,
+        types: Vec<DataProducerTraceEventType>,
+    ) -> Result<(), RequestError> {
+        debug!("enable_trace_event()");
+
+        self.inner()
+            .channel
+            .request(DataProducerEnableTraceEventRequest {
+                internal: self.get_internal(),
+                data: DataProducerEnableTraceEventData { types },
+            })
+            .await
+    }
+
+    /// Callback is called when [`DataProducer::buffered_amount`] drops to or below
+    /// [`DataProducerOptions::buffered_amount_low_threshold`], having previously been above it.
+    pub fn on_buffered_amount_low<F: Fn() + Send + Sync + 'static>(
+        &self,
+        callback: F,
+    ) -> HandlerId {
+        self.inner()
+            .handlers
+            .buffered_amount_low
+            .add(Box::new(callback))
+    }
+
+    /// Callback is called with each sample taken by the background poller enabled via
+    /// [`DataProducerOptions::stats_poll_interval`], along with the throughput deltas computed
+    /// against the previous sample.
+    pub fn on_stats<F: Fn(&DataProducerStat, &DataProducerStatsDelta) + Send + Sync + 'static>(
+        &self,
+        callback: F,
+    ) -> HandlerId {
+        self.inner().handlers.stats.add(Box::new(callback))
+    }
+
+    /// Callback is called when the data producer is paused.
+    pub fn on_pause<F: Fn() + Send + Sync + 'static>(&self, callback: F) -> HandlerId {
+        self.inner().handlers.pause.add(Box::new(callback))
+    }
+
+    /// Callback is called when the data producer is resumed.
+    pub fn on_resume<F: Fn() + Send + Sync + 'static>(&self, callback: F) -> HandlerId {
+        self.inner().handlers.resume.add(Box::new(callback))
+    }
+
+    /// See [`DataProducer::enable_trace_event`] method.
+    pub fn on_trace<F: Fn(&DataProducerTraceEventData) + Send + Sync + 'static>(
+        &self,
+        callback: F,
+    ) -> HandlerId {
+        self.inner().handlers.trace.add(Box::new(callback))
+    }
+
     pub fn on_transport_close<F: FnOnce() + Send + 'static>(&self, callback: F) -> HandlerId {
         self.inner()
             .handlers
@@ -354,14 +793,47 @@ impl DataProducer {
     }
 }
 
+/// Error produced by [`DirectDataProducer::send`].
+#[derive(Debug, Error)]
+pub enum SendError {
+    /// Buffered amount of data not yet acknowledged by the worker exceeds the configured high
+    /// water mark; wait for [`DataProducer::on_buffered_amount_low`] before sending more.
+    #[error(
+        "buffered amount {buffered_amount} exceeds high water mark {high_water_mark}"
+    )]
+    TooMuchBufferedAmount {
+        buffered_amount: u32,
+        high_water_mark: u32,
+    },
+    /// Underlying notification to the worker failed.
+    #[error(transparent)]
+    Notification(#[from] NotificationError),
+}
+
 impl DirectDataProducer {
     /// Send data.
-    pub async fn send(&self, message: WebRtcMessage) -> Result<(), NotificationError> {
+    pub async fn send(&self, message: WebRtcMessage) -> Result<(), SendError> {
+        let buffered_amount = self.inner.buffered_amount.load(Ordering::SeqCst);
+        let high_water_mark = self.inner.buffered_amount_high_water_mark;
+
+        if buffered_amount > high_water_mark {
+            return Err(SendError::TooMuchBufferedAmount {
+                buffered_amount,
+                high_water_mark,
+            });
+        }
+
         let (ppid, payload) = message.into_ppid_and_payload();
+        let payload_len = payload.len() as u32;
 
         self.inner
+            .buffered_amount
+            .fetch_add(payload_len, Ordering::SeqCst);
+
+        let result = self
+            .inner
             .payload_channel
-            .notify(
+            .notify_with(
                 DataProducerSendNotification {
                     internal: DataProducerInternal {
                         router_id: self.inner.transport.router_id(),
@@ -371,8 +843,105 @@ impl DirectDataProducer {
                     data: DataProducerSendData { ppid },
                 },
                 payload,
+                RequestPriority::BACKGROUND,
             )
-            .await
+            .await;
+
+        if result.is_err() {
+            self.inner
+                .buffered_amount
+                .fetch_sub(payload_len, Ordering::SeqCst);
+        }
+
+        result.map_err(SendError::from)
+    }
+}
+
+impl futures::Sink<WebRtcMessage> for DirectDataProducer {
+    type Error = SendError;
+
+    /// Ready once [`DataProducer::buffered_amount`] is at or below the configured high water
+    /// mark, so the sink's own backpressure mirrors [`DirectDataProducer::send`]'s.
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let buffered_amount = self.inner.buffered_amount.load(Ordering::SeqCst);
+        let high_water_mark = self.inner.buffered_amount_high_water_mark;
+
+        if buffered_amount <= high_water_mark {
+            return Poll::Ready(Ok(()));
+        }
+
+        *self.inner.ready_waker.lock() = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Enqueues the notification to the worker on the executor and returns immediately;
+    /// [`Sink::poll_flush`]/[`Sink::poll_close`] are what actually wait for it to be queued on
+    /// the payload channel.
+    fn start_send(self: Pin<&mut Self>, item: WebRtcMessage) -> Result<(), Self::Error> {
+        let (ppid, payload) = item.into_ppid_and_payload();
+        let payload_len = payload.len() as u32;
+
+        self.inner
+            .buffered_amount
+            .fetch_add(payload_len, Ordering::SeqCst);
+        self.inner.outstanding_sends.fetch_add(1, Ordering::SeqCst);
+
+        let inner = Arc::clone(&self.inner);
+        self.inner
+            .executor
+            .spawn(async move {
+                let result = inner
+                    .payload_channel
+                    .notify_with(
+                        DataProducerSendNotification {
+                            internal: DataProducerInternal {
+                                router_id: inner.transport.router_id(),
+                                transport_id: inner.transport.id(),
+                                data_producer_id: inner.id,
+                            },
+                            data: DataProducerSendData { ppid },
+                        },
+                        payload,
+                        RequestPriority::BACKGROUND,
+                    )
+                    .await;
+
+                if result.is_err() {
+                    inner.buffered_amount.fetch_sub(payload_len, Ordering::SeqCst);
+                }
+
+                if inner.outstanding_sends.fetch_sub(1, Ordering::SeqCst) == 1 {
+                    if let Some(waker) = inner.flush_waker.lock().take() {
+                        waker.wake();
+                    }
+                }
+            })
+            .detach();
+
+        Ok(())
+    }
+
+    /// Waits for every notification enqueued by [`Sink::start_send`] to have been handed to the
+    /// payload channel's send queue (successfully or not). The worker does not acknowledge these
+    /// notifications, so this does not wait for them to be written out or processed.
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.inner.outstanding_sends.load(Ordering::SeqCst) == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        *self.inner.flush_waker.lock() = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Flushes outstanding sends, then closes the data producer.
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.as_mut().poll_flush(cx) {
+            Poll::Ready(Ok(())) => {
+                DataProducer::Direct(self.get_mut().clone()).close();
+                Poll::Ready(Ok(()))
+            }
+            other => other,
+        }
     }
 }
 
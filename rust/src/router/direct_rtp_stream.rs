@@ -0,0 +1,127 @@
+//! Async `Stream`-based delivery of forwarded RTP packets with explicit, bounded backpressure,
+//! as an alternative to registering a synchronous callback via [`Consumer::on_rtp`] that has no
+//! flow control of its own.
+
+use crate::consumer::Consumer;
+use async_channel::{Receiver, Sender};
+use bytes::Bytes;
+use event_listener_primitives::HandlerId;
+use futures_lite::Stream;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// What to do when [`Consumer::direct_rtp_stream`]'s bounded channel is full.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// Evict the oldest buffered packet to make room for the new one.
+    DropOldest,
+    /// Discard the newly arrived packet, keeping the buffered ones.
+    DropNewest,
+    /// Apply backpressure: block the payload-channel subscription until the consumer drains.
+    Block,
+}
+
+/// Counts packets dropped by [`OverflowPolicy::DropOldest`]/[`OverflowPolicy::DropNewest`] for a
+/// given [`Consumer::direct_rtp_stream`] subscription.
+#[derive(Debug, Clone, Default)]
+pub struct DroppedPacketCounter {
+    count: Arc<AtomicUsize>,
+}
+
+impl DroppedPacketCounter {
+    /// Number of packets dropped so far due to the stream's overflow policy.
+    pub fn get(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn increment(&self) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// A bounded `Stream` of forwarded RTP packets, backed by [`async_channel`], along with the
+/// dropped-packet counter for the configured [`OverflowPolicy`].
+pub struct DirectRtpStream {
+    receiver: Receiver<Bytes>,
+    dropped: DroppedPacketCounter,
+    // Unsubscribes from `on_rtp` when the stream is dropped.
+    _handler_id: HandlerId,
+}
+
+impl DirectRtpStream {
+    /// Number of packets dropped so far due to the stream's overflow policy.
+    pub fn dropped_packets(&self) -> usize {
+        self.dropped.get()
+    }
+}
+
+impl futures_lite::Stream for DirectRtpStream {
+    type Item = Bytes;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        std::pin::Pin::new(&mut this.receiver).poll_next(cx)
+    }
+}
+
+/// Pushes `packet` into `sender` according to `policy`, dropping and counting as needed.
+pub(super) fn push_with_policy(
+    sender: &Sender<Bytes>,
+    dropped: &DroppedPacketCounter,
+    policy: OverflowPolicy,
+    packet: Bytes,
+) {
+    match sender.try_send(packet) {
+        Ok(()) => {}
+        Err(async_channel::TrySendError::Full(packet)) => match policy {
+            OverflowPolicy::DropNewest => {
+                dropped.increment();
+            }
+            OverflowPolicy::DropOldest => {
+                // Evict one buffered packet to make room, then retry once.
+                if sender.try_recv().is_ok() {
+                    dropped.increment();
+                }
+                let _ = sender.try_send(packet);
+            }
+            OverflowPolicy::Block => {
+                // Blocks the notification dispatch thread until the consumer drains, applying
+                // backpressure upstream instead of growing memory or dropping packets.
+                let _ = sender.send_blocking(packet);
+            }
+        },
+        Err(async_channel::TrySendError::Closed(_)) => {}
+    }
+}
+
+impl Consumer {
+    /// Returns a bounded `Stream` of RTP packets forwarded to this consumer (direct transports
+    /// only, see [`Consumer::on_rtp`]), applying `overflow_policy` once `capacity` packets are
+    /// buffered instead of growing memory unboundedly.
+    pub fn direct_rtp_stream(
+        &self,
+        capacity: usize,
+        overflow_policy: OverflowPolicy,
+    ) -> DirectRtpStream {
+        let (sender, receiver) = async_channel::bounded(capacity.max(1));
+        let dropped = DroppedPacketCounter::default();
+
+        let handler_id = {
+            let sender = sender.clone();
+            let dropped = dropped.clone();
+
+            self.on_rtp(move |packet: &Bytes| {
+                push_with_policy(&sender, &dropped, overflow_policy, packet.clone());
+            })
+        };
+
+        DirectRtpStream {
+            receiver,
+            dropped,
+            _handler_id: handler_id,
+        }
+    }
+}
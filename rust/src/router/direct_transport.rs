@@ -0,0 +1,255 @@
+//! A direct transport exchanges raw application payloads with the worker directly, without SCTP
+//! or RTP, by moving bytes over the worker `Channel`. Because the channel truncates messages
+//! above [`CHUNK_SIZE`], payloads are split into fixed-size chunks here and reassembled on the
+//! receive side.
+
+use crate::data_structures::TransportInternal;
+use crate::router::transport::TransportId;
+use crate::router::RouterId;
+use crate::worker::{Channel, RequestError, SubscriptionHandler};
+use event_listener_primitives::{Bag, HandlerId};
+use log::error;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Maximum payload carried by a single chunk. The worker channel truncates anything larger, so
+/// outgoing messages above this size are split across multiple chunks.
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// Chunk framing flag, carried as the `flag` field alongside every chunk's data.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(u8)]
+enum ChunkFlag {
+    /// First chunk of a multi-chunk message.
+    Begin = 0b01,
+    /// Neither first nor last chunk of a multi-chunk message.
+    Continue = 0b00,
+    /// Last chunk of a multi-chunk message.
+    End = 0b10,
+    /// The only chunk of a single-chunk message (begin and end).
+    BeginAndEnd = 0b11,
+}
+
+impl ChunkFlag {
+    fn is_end(self) -> bool {
+        (self as u8) & (ChunkFlag::End as u8) != 0
+    }
+}
+
+/// A single framed chunk of a `DirectTransport` message: a message id (to disambiguate
+/// interleaved messages), a flag byte, and the chunk's slice of the payload.
+struct Chunk {
+    message_id: u32,
+    flag: ChunkFlag,
+    data: Vec<u8>,
+}
+
+/// Splits `payload` into [`CHUNK_SIZE`]-sized chunks tagged with `message_id`, in send order.
+fn frame_payload(message_id: u32, payload: &[u8]) -> Vec<Chunk> {
+    if payload.is_empty() {
+        return vec![Chunk {
+            message_id,
+            flag: ChunkFlag::BeginAndEnd,
+            data: Vec::new(),
+        }];
+    }
+
+    let chunks = payload.chunks(CHUNK_SIZE).collect::<Vec<_>>();
+    let last_index = chunks.len() - 1;
+
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, data)| {
+            let flag = match (index == 0, index == last_index) {
+                (true, true) => ChunkFlag::BeginAndEnd,
+                (true, false) => ChunkFlag::Begin,
+                (false, true) => ChunkFlag::End,
+                (false, false) => ChunkFlag::Continue,
+            };
+
+            Chunk {
+                message_id,
+                flag,
+                data: data.to_vec(),
+            }
+        })
+        .collect()
+}
+
+/// Reassembles chunks received out of a single message id's stream, buffering partial messages
+/// until their `End` chunk arrives.
+#[derive(Default)]
+struct Reassembler {
+    /// Partial buffers keyed by message id, so interleaved messages from different ids don't
+    /// corrupt one another.
+    partial: Mutex<HashMap<u32, Vec<u8>>>,
+}
+
+impl Reassembler {
+    /// Feeds a single received chunk, returning the fully reassembled message once its `End`
+    /// chunk has arrived.
+    fn receive(&self, chunk: Chunk) -> Option<Vec<u8>> {
+        let mut partial = self.partial.lock().unwrap();
+
+        let buffer = partial.entry(chunk.message_id).or_insert_with(Vec::new);
+        buffer.extend_from_slice(&chunk.data);
+
+        if chunk.flag.is_end() {
+            return partial.remove(&chunk.message_id);
+        }
+
+        None
+    }
+
+    /// Decodes a chunk notification's raw `flag` byte and feeds it to [`Self::receive`]; called
+    /// for every `chunk` notification the worker sends for this transport.
+    fn receive_chunk(&self, message_id: u32, flag_byte: u8, data: Vec<u8>) -> Option<Vec<u8>> {
+        let flag = match flag_byte {
+            0b11 => ChunkFlag::BeginAndEnd,
+            0b01 => ChunkFlag::Begin,
+            0b10 => ChunkFlag::End,
+            _ => ChunkFlag::Continue,
+        };
+
+        self.receive(Chunk {
+            message_id,
+            flag,
+            data,
+        })
+    }
+
+    /// Drops any partial buffers, called when the owning transport closes.
+    fn clear(&self) {
+        self.partial.lock().unwrap().clear();
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase", content = "data")]
+enum Notification {
+    #[serde(rename_all = "camelCase")]
+    Chunk {
+        message_id: u32,
+        flag: u8,
+        ppid: u32,
+        data: Vec<u8>,
+    },
+}
+
+#[derive(Default)]
+struct Handlers {
+    message: Bag<dyn Fn(&[u8], u32) + Send + Sync>,
+}
+
+/// A transport that exchanges raw application payloads directly with the worker, split into
+/// [`CHUNK_SIZE`] chunks and reassembled transparently.
+pub struct DirectTransport {
+    id: TransportId,
+    router_id: RouterId,
+    channel: Channel,
+    next_message_id: Mutex<u32>,
+    reassembler: Arc<Reassembler>,
+    handlers: Arc<Handlers>,
+    _subscription_handler: SubscriptionHandler,
+}
+
+impl Drop for DirectTransport {
+    fn drop(&mut self) {
+        self.reassembler.clear();
+    }
+}
+
+impl DirectTransport {
+    /// Creates a handle for an already-opened direct transport, subscribing to its `chunk`
+    /// notifications so reassembled messages are handed to callbacks registered via
+    /// [`Self::on_message`].
+    pub(crate) async fn new(id: TransportId, router_id: RouterId, channel: Channel) -> Self {
+        let reassembler = Arc::new(Reassembler::default());
+        let handlers = Arc::new(Handlers::default());
+
+        let subscription_handler = {
+            let reassembler = Arc::clone(&reassembler);
+            let handlers = Arc::clone(&handlers);
+
+            channel
+                .subscribe_to_notifications(id.to_string(), move |notification| {
+                    match serde_json::from_value::<Notification>(notification) {
+                        Ok(Notification::Chunk {
+                            message_id,
+                            flag,
+                            ppid,
+                            data,
+                        }) => {
+                            if let Some(payload) = reassembler.receive_chunk(message_id, flag, data)
+                            {
+                                handlers.message.call(|callback| {
+                                    callback(&payload, ppid);
+                                });
+                            }
+                        }
+                        Err(error) => {
+                            error!("Failed to parse notification: {}", error);
+                        }
+                    }
+                })
+                .await
+        };
+
+        Self {
+            id,
+            router_id,
+            channel,
+            next_message_id: Mutex::new(0),
+            reassembler,
+            handlers,
+            _subscription_handler: subscription_handler,
+        }
+    }
+
+    /// Callback is called for every message received on this direct transport, once its chunks
+    /// have been reassembled.
+    pub fn on_message<F: Fn(&[u8], u32) + Send + Sync + 'static>(&self, callback: F) -> HandlerId {
+        self.handlers.message.add(Box::new(callback))
+    }
+
+    /// Sends `payload` with the given `ppid`, chunking it and awaiting each chunk's channel ack
+    /// before emitting the next one so a large message cannot exhaust the channel.
+    pub async fn send(&self, payload: &[u8], ppid: u32) -> Result<(), RequestError> {
+        let message_id = {
+            let mut next_message_id = self.next_message_id.lock().unwrap();
+            let message_id = *next_message_id;
+            *next_message_id = next_message_id.wrapping_add(1);
+            message_id
+        };
+
+        for chunk in frame_payload(message_id, payload) {
+            self.channel
+                .request(DirectTransportSendChunkRequest {
+                    internal: TransportInternal {
+                        router_id: self.router_id,
+                        transport_id: self.id,
+                    },
+                    message_id: chunk.message_id,
+                    flag: chunk.flag as u8,
+                    ppid,
+                    data: chunk.data,
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Chunked payload delivery request issued by [`DirectTransport::send`].
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DirectTransportSendChunkRequest {
+    internal: TransportInternal,
+    message_id: u32,
+    flag: u8,
+    ppid: u32,
+    data: Vec<u8>,
+}
@@ -0,0 +1,235 @@
+//! Keyframe-delimited RTP grouping for relay/fan-out egress, adopting the group/fragment model
+//! from Media-over-QUIC (moq-transport): forwarded RTP is grouped into segments that each begin
+//! at a decodable keyframe boundary, so relay/CDN builders get cache-friendly, priority-tagged
+//! chunking without reimplementing keyframe detection on top of the flat per-packet callback.
+
+use crate::consumer::{Consumer, ConsumerTraceEventData};
+use async_channel::{Receiver, Sender};
+use bytes::Bytes;
+use event_listener_primitives::HandlerId;
+use futures_lite::Stream;
+use std::time::{Duration, Instant};
+
+/// Tuning knobs for [`Consumer::segmented_rtp_stream`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SegmentConfig {
+    /// Fragments buffered per segment; once full, newly arriving fragments of that segment are
+    /// dropped, so the keyframe that opened it is kept.
+    pub fragment_capacity: usize,
+    /// Segments buffered before the oldest pending segment is dropped.
+    pub segment_capacity: usize,
+    /// Priority assigned to every segment; higher sorts first when a relay is deciding what to
+    /// keep under pressure.
+    pub priority: u8,
+    /// Time-to-live for a segment after it starts, for drop-stale-on-expiry policies.
+    pub expires_after: Option<Duration>,
+}
+
+impl Default for SegmentConfig {
+    fn default() -> Self {
+        Self {
+            fragment_capacity: 256,
+            segment_capacity: 4,
+            priority: 0,
+            expires_after: None,
+        }
+    }
+}
+
+/// Metadata describing one keyframe-delimited segment.
+#[derive(Debug, Clone, Copy)]
+pub struct SegmentInfo {
+    /// Monotonically increasing segment sequence number.
+    pub sequence: u64,
+    /// Priority copied from [`SegmentConfig::priority`] at the time the segment was opened.
+    pub priority: u8,
+    /// Instant after which the segment is considered stale, if `expires_after` was set.
+    pub expires: Option<Instant>,
+}
+
+/// The fragment stream of a single segment: the keyframe packet followed by its delta packets,
+/// until the next keyframe opens a new segment.
+pub struct SegmentFragments {
+    receiver: Receiver<Bytes>,
+}
+
+impl futures_lite::Stream for SegmentFragments {
+    type Item = Bytes;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        std::pin::Pin::new(&mut this.receiver).poll_next(cx)
+    }
+}
+
+/// Stream of `(SegmentInfo, SegmentFragments)` pairs, one per keyframe-delimited segment.
+pub struct SegmentedRtpStream {
+    receiver: Receiver<(SegmentInfo, SegmentFragments)>,
+    // Unsubscribe from `on_rtp`/`on_trace` when the stream is dropped.
+    _rtp_handler_id: HandlerId,
+    _trace_handler_id: HandlerId,
+}
+
+impl futures_lite::Stream for SegmentedRtpStream {
+    type Item = (SegmentInfo, SegmentFragments);
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        std::pin::Pin::new(&mut this.receiver).poll_next(cx)
+    }
+}
+
+/// A segment boundary announced by `on_trace`'s `KeyFrame` handler, not yet applied because the
+/// keyframe's own RTP packet hasn't reached `on_rtp` yet (the two callbacks fire off independent,
+/// independently-scheduled notification pipes, so there's no ordering guarantee between them).
+/// Keyed by the keyframe's RTP timestamp (carried in the trace event's `info.rtpPacket.timestamp`)
+/// so `on_rtp` can recognize the exact packet that opens the segment rather than guessing from
+/// arrival order.
+struct PendingBoundary {
+    rtp_timestamp: u32,
+    info: SegmentInfo,
+    fragment_sender: Sender<Bytes>,
+    fragment_receiver: Receiver<Bytes>,
+}
+
+/// Mutable state shared between the `on_rtp` and `on_trace` callbacks: the fragment sender of the
+/// currently open segment, the next segment's sequence number, and any boundaries `on_trace` has
+/// announced that `on_rtp` hasn't matched to a packet yet.
+struct SegmenterState {
+    current_fragments: Option<Sender<Bytes>>,
+    next_sequence: u64,
+    pending_boundaries: std::collections::VecDeque<PendingBoundary>,
+}
+
+/// Extracts the 32-bit RTP timestamp (bytes 4..8 of the fixed RTP header, network byte order) from
+/// a raw packet, or `None` if it's too short to contain one.
+fn rtp_timestamp(packet: &[u8]) -> Option<u32> {
+    packet.get(4..8).map(|bytes| u32::from_be_bytes(bytes.try_into().expect("slice is 4 bytes")))
+}
+
+/// Extracts the RTP timestamp mediasoup attaches to a `KeyFrame` trace event's `info.rtpPacket`,
+/// which identifies the exact RTP packet the keyframe notification is about.
+fn keyframe_rtp_timestamp(info: &serde_json::Value) -> Option<u32> {
+    info.get("rtpPacket")?
+        .get("timestamp")?
+        .as_u64()
+        .map(|timestamp| timestamp as u32)
+}
+
+impl Consumer {
+    /// Groups forwarded RTP into keyframe-delimited segments, each starting at a decodable
+    /// keyframe boundary detected via the existing [`ConsumerTraceEventType::KeyFrame`] trace
+    /// path. Requires trace events to be enabled for `KeyFrame` (see
+    /// [`Consumer::enable_trace_event`]).
+    ///
+    /// [`ConsumerTraceEventType::KeyFrame`]: crate::consumer::ConsumerTraceEventType::KeyFrame
+    pub fn segmented_rtp_stream(&self, config: SegmentConfig) -> SegmentedRtpStream {
+        let (segment_sender, segment_receiver) = async_channel::bounded(config.segment_capacity.max(1));
+        let state = parking_lot::Mutex::new(SegmenterState {
+            current_fragments: None,
+            next_sequence: 0,
+            pending_boundaries: std::collections::VecDeque::new(),
+        });
+        let state = std::sync::Arc::new(state);
+
+        let rtp_handler_id = {
+            let state = std::sync::Arc::clone(&state);
+            let segment_sender = segment_sender.clone();
+            let segment_evictor = segment_receiver.clone();
+
+            self.on_rtp(move |packet: &Bytes| {
+                let mut state = state.lock();
+
+                // A pending boundary only ever applies to the packet whose own RTP timestamp
+                // matches the one the keyframe trace event reported, so a boundary that hasn't
+                // been matched yet never blocks unrelated packets from reaching the current
+                // segment below. Boundaries aren't necessarily resolved in the order `on_trace`
+                // queued them in (its pipe and `on_rtp`'s are independently scheduled, so a later
+                // keyframe's packet can outrun an earlier one's), so match against any pending
+                // boundary, not just the front.
+                if let Some(timestamp) = rtp_timestamp(packet) {
+                    let matched_index = state
+                        .pending_boundaries
+                        .iter()
+                        .position(|boundary| boundary.rtp_timestamp == timestamp);
+
+                    if let Some(matched_index) = matched_index {
+                        let boundary = state
+                            .pending_boundaries
+                            .remove(matched_index)
+                            .expect("just matched Some above");
+
+                        let _ = boundary.fragment_sender.try_send(packet.clone());
+                        state.current_fragments = Some(boundary.fragment_sender);
+                        let segment = (
+                            boundary.info,
+                            SegmentFragments {
+                                receiver: boundary.fragment_receiver,
+                            },
+                        );
+                        if let Err(async_channel::TrySendError::Full(segment)) =
+                            segment_sender.try_send(segment)
+                        {
+                            // Evict the oldest pending segment to make room for the newest one.
+                            let _ = segment_evictor.try_recv();
+                            let _ = segment_sender.try_send(segment);
+                        }
+                        return;
+                    }
+                }
+
+                if let Some(sender) = &state.current_fragments {
+                    let _ = sender.try_send(packet.clone());
+                }
+            })
+        };
+
+        let trace_handler_id = {
+            let state = std::sync::Arc::clone(&state);
+            let fragment_capacity = config.fragment_capacity.max(1);
+            let priority = config.priority;
+            let expires_after = config.expires_after;
+
+            self.on_trace(move |trace_event_data: &ConsumerTraceEventData| {
+                let ConsumerTraceEventData::KeyFrame { info, .. } = trace_event_data else {
+                    return;
+                };
+
+                let Some(rtp_timestamp) = keyframe_rtp_timestamp(info) else {
+                    return;
+                };
+
+                let mut state = state.lock();
+                let sequence = state.next_sequence;
+                state.next_sequence += 1;
+
+                let (fragment_sender, fragment_receiver) =
+                    async_channel::bounded(fragment_capacity);
+
+                state.pending_boundaries.push_back(PendingBoundary {
+                    rtp_timestamp,
+                    info: SegmentInfo {
+                        sequence,
+                        priority,
+                        expires: expires_after.map(|ttl| Instant::now() + ttl),
+                    },
+                    fragment_sender,
+                    fragment_receiver,
+                });
+            })
+        };
+
+        SegmentedRtpStream {
+            receiver: segment_receiver,
+            _rtp_handler_id: rtp_handler_id,
+            _trace_handler_id: trace_handler_id,
+        }
+    }
+}
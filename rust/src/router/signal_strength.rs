@@ -0,0 +1,120 @@
+//! A coarse, hysteresis-stabilized signal-strength rating derived purely from the consumer's own
+//! score stream and current layer selection, borrowing medea's `ConnectionQualityScore` concept:
+//! an exponential moving average of [`ConsumerScore::score`], pulled down while the consumer's
+//! current layers are its lowest ones (or `None`), so apps get a simple signal-strength indicator
+//! without re-implementing smoothing on top of the noisy raw score. The cause of being at the
+//! lowest layers or `None` is not distinguished: a paused consumer, or one with no layers at all,
+//! is pulled down the same way as one limited by bandwidth.
+//!
+//! This is deliberately a separate signal from [`crate::router::connection_quality::ConnectionQuality`]:
+//! that one blends in transport-level stats (loss, RTT, NACK/PLI growth) sampled afresh on each
+//! score report, while this one smooths the score alone through an EMA and looks only at
+//! score/layers. Both require the same number of consecutive samples before a rating changes.
+//! Names used to collide
+//! (`ConnectionQualityScore` next to `ConnectionQuality`); this module keeps the distinct "signal
+//! strength" name so the two aren't mistaken for variations of the same rating.
+
+use crate::consumer::{ConsumerLayers, ConsumerScore};
+
+/// Coarse signal-strength rating for a [`crate::consumer::Consumer`], reported via
+/// [`crate::consumer::Consumer::on_signal_strength`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum SignalStrength {
+    Poor,
+    Low,
+    Medium,
+    High,
+}
+
+impl SignalStrength {
+    /// Buckets a `[0.0, 10.0]` EMA value into a rating.
+    fn from_ema(ema: f64) -> Self {
+        if ema >= 7.5 {
+            SignalStrength::High
+        } else if ema >= 5.0 {
+            SignalStrength::Medium
+        } else if ema >= 2.5 {
+            SignalStrength::Low
+        } else {
+            SignalStrength::Poor
+        }
+    }
+}
+
+/// Smoothing factor of the score EMA: higher reacts faster, lower is steadier.
+const EMA_ALPHA: f64 = 0.2;
+
+/// How many EMA points a pinned-to-lowest-layer consumer is marked down by before bucketing,
+/// since a healthy score at the floor layer still means "bandwidth is constrained".
+const LAYER_PINNED_PENALTY: f64 = 2.0;
+
+/// Consecutive updates a candidate rating must survive before it replaces the reported one, so
+/// the signal does not flap on every score tick.
+const HYSTERESIS_UPDATES: u32 = 3;
+
+/// Tracks the EMA/layer-pinned state described above and derives a hysteresis-stabilized
+/// [`SignalStrength`].
+pub(super) struct SignalStrengthTracker {
+    ema: f64,
+    current: SignalStrength,
+    pending: Option<(SignalStrength, u32)>,
+}
+
+impl Default for SignalStrengthTracker {
+    fn default() -> Self {
+        Self {
+            ema: 10.0,
+            current: SignalStrength::High,
+            pending: None,
+        }
+    }
+}
+
+impl SignalStrengthTracker {
+    /// Feeds a new score sample and the consumer's current layer selection, returning
+    /// `Some(rating)` only once a candidate rating has held for [`HYSTERESIS_UPDATES`] in a row.
+    pub(super) fn on_score(
+        &mut self,
+        score: &ConsumerScore,
+        current_layers: Option<ConsumerLayers>,
+    ) -> Option<SignalStrength> {
+        self.ema = EMA_ALPHA * f64::from(score.score) + (1.0 - EMA_ALPHA) * self.ema;
+
+        let pinned_to_lowest = matches!(
+            current_layers,
+            None | Some(ConsumerLayers {
+                spatial_layer: 0,
+                temporal_layer: None | Some(0),
+            })
+        );
+
+        let adjusted = if pinned_to_lowest {
+            (self.ema - LAYER_PINNED_PENALTY).max(0.0)
+        } else {
+            self.ema
+        };
+
+        let candidate = SignalStrength::from_ema(adjusted);
+
+        if candidate == self.current {
+            self.pending = None;
+            return None;
+        }
+
+        match &mut self.pending {
+            Some((pending_candidate, streak)) if *pending_candidate == candidate => {
+                *streak += 1;
+                if *streak >= HYSTERESIS_UPDATES {
+                    self.current = candidate;
+                    self.pending = None;
+                    return Some(candidate);
+                }
+            }
+            _ => {
+                self.pending = Some((candidate, 1));
+            }
+        }
+
+        None
+    }
+}
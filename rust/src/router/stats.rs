@@ -0,0 +1,90 @@
+//! Standardized, strongly-typed statistics modeled on the W3C `RTCStats` dictionary hierarchy.
+//!
+//! Unlike the mediasoup-native `*Stat` structs returned by other parts of this crate, `RtcStats`
+//! dispatches on the worker's `type` discriminator so callers get compile-time field access
+//! instead of re-parsing an opaque JSON blob themselves.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A W3C-shaped statistics snapshot, keyed by stat id (as returned by `RTCPeerConnection.getStats()`
+/// in a browser).
+pub type RtcStatsReport = HashMap<String, RtcStats>;
+
+/// A single W3C-shaped statistics entry, tagged by its `type` discriminator.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum RtcStats {
+    Transport {
+        /// Event timestamp, milliseconds since epoch.
+        timestamp: f64,
+        bytes_sent: u64,
+        bytes_received: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        available_outgoing_bitrate: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        available_incoming_bitrate: Option<u64>,
+        dtls_state: String,
+        ice_role: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        selected_candidate_pair_id: Option<String>,
+    },
+    #[serde(rename = "inbound-rtp")]
+    InboundRtp {
+        timestamp: f64,
+        ssrc: u32,
+        kind: String,
+        bytes_received: u64,
+        packets_lost: i32,
+        jitter: f64,
+    },
+    #[serde(rename = "outbound-rtp")]
+    OutboundRtp {
+        timestamp: f64,
+        ssrc: u32,
+        kind: String,
+        packets_sent: u64,
+        bytes_sent: u64,
+        nack_count: u64,
+        pli_count: u64,
+        fir_count: u64,
+        retransmitted_packets_sent: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        round_trip_time: Option<f64>,
+    },
+    #[serde(rename = "remote-inbound-rtp")]
+    RemoteInboundRtp {
+        timestamp: f64,
+        ssrc: u32,
+        packets_lost: i32,
+        fraction_lost: f64,
+        jitter: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        round_trip_time: Option<f64>,
+    },
+    #[serde(rename = "candidate-pair")]
+    CandidatePair {
+        timestamp: f64,
+        state: String,
+        bytes_sent: u64,
+        bytes_received: u64,
+        local_candidate_id: String,
+        remote_candidate_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        available_outgoing_bitrate: Option<u64>,
+    },
+    #[serde(rename = "local-candidate")]
+    LocalCandidate { timestamp: f64, ip: String, port: u16, protocol: String },
+    #[serde(rename = "remote-candidate")]
+    RemoteCandidate { timestamp: f64, ip: String, port: u16, protocol: String },
+}
+
+impl TryFrom<serde_json::Value> for RtcStats {
+    type Error = serde_json::Error;
+
+    /// Dispatches on the `type` field of the raw worker payload into the matching variant.
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        serde_json::from_value(value)
+    }
+}
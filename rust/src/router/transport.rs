@@ -1,12 +1,24 @@
+use crate::consumer::{Consumer, ConsumerId, ConsumerOptions, ConsumerScore, ConsumerType};
+use crate::data_consumer::{DataConsumer, DataConsumerId, DataConsumerOptions, DataConsumerType};
+use crate::data_producer::{DataProducer, DataProducerId, DataProducerOptions, DataProducerType};
 use crate::data_structures::{AppData, TransportInternal};
 use crate::messages::{
-    TransportDumpRequest, TransportGetStatsRequest, TransportSetMaxIncomingBitrateData,
+    DataConsumerInternal, DataProducerInternal, TransportConsumeData, TransportConsumeDataData,
+    TransportConsumeDataRequest, TransportConsumeRequest, TransportDumpRequest,
+    TransportEnableTraceEventData, TransportEnableTraceEventRequest, TransportGetStatsRequest,
+    TransportProduceDataData, TransportProduceDataRequest, TransportSetMaxIncomingBitrateData,
     TransportSetMaxIncomingBitrateRequest,
 };
 use crate::producer::{Producer, ProducerOptions};
+use crate::router::stats::RtcStats;
 use crate::router::RouterId;
+use crate::rtp_parameters::{MediaKind, RtpParameters};
+use crate::sctp_parameters::SctpStreamParameters;
 use crate::uuid_based_wrapper_type;
-use crate::worker::{Channel, RequestError};
+use crate::worker::channels::RequestPriority;
+use crate::worker::request_options::RequestOptions;
+use crate::worker::{Channel, PayloadChannel, RequestError};
+use async_executor::Executor;
 use async_trait::async_trait;
 use futures_lite::FutureExt;
 use log::debug;
@@ -17,6 +29,7 @@ use std::fmt::Debug;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::sync::Arc;
 
 uuid_based_wrapper_type!(TransportId);
 
@@ -50,6 +63,16 @@ pub enum TransportTraceEventData {
     },
 }
 
+/// Types of transport trace events.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportTraceEventType {
+    /// Bandwidth estimation probation packet.
+    Probation,
+    /// Bandwidth estimation report.
+    Bwe,
+}
+
 #[async_trait]
 pub trait Transport<Dump, Stat, RemoteParameters> {
     /// Transport id.
@@ -62,6 +85,10 @@ pub trait Transport<Dump, Stat, RemoteParameters> {
     async fn dump(&self) -> Result<Dump, RequestError>;
 
     /// Get Transport stats.
+    ///
+    /// Returns the transport's mediasoup-native `Stat` type (e.g. `WebRtcTransportStat`), not
+    /// [`RtcStats`]; the standardized W3C `RTCStats` shape is provided separately, e.g. by
+    /// `WebRtcTransport::get_standard_stats`.
     async fn get_stats(&self) -> Result<Vec<Stat>, RequestError>;
 
     /// Provide the Transport remote parameters.
@@ -71,10 +98,57 @@ pub trait Transport<Dump, Stat, RemoteParameters> {
 
     async fn produce(&self, producer_options: ProducerOptions) -> Result<Producer, RequestError>;
 
+    /// Creates a Consumer for receiving the given Producer's media over this transport.
+    async fn consume(&self, consumer_options: ConsumerOptions) -> Result<Consumer, RequestError>;
+
+    /// Creates a DataProducer for sending SCTP/DataChannel messages over this transport.
+    async fn produce_data(
+        &self,
+        data_producer_options: DataProducerOptions,
+    ) -> Result<DataProducer, RequestError>;
+
+    /// Creates a DataConsumer for receiving the SCTP/DataChannel messages of the given
+    /// DataProducer over this transport.
+    async fn consume_data(
+        &self,
+        data_consumer_options: DataConsumerOptions,
+    ) -> Result<DataConsumer, RequestError>;
+
+    /// Instructs the transport to emit "trace" events for the given types. For monitoring
+    /// purposes, to observe bandwidth-estimation and probation events live via [`Transport::on_trace`].
+    async fn enable_trace_event(
+        &self,
+        types: &[TransportTraceEventType],
+    ) -> Result<(), RequestError>;
+
+    /// See [`Transport::enable_trace_event`].
+    fn on_trace<F: Fn(&TransportTraceEventData) + Send + 'static>(&self, callback: F);
+
     fn connect_closed<F: FnOnce() + Send + 'static>(&self, callback: F);
     // TODO
 }
 
+/// Reply to the `transport.consume` request: the consumer's initial pause state (its own and its
+/// producer's) and score, the only parts of the worker's response [`Self::consume_impl`] needs
+/// that aren't already known from [`ConsumerOptions`].
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConsumeResponse {
+    paused: bool,
+    producer_paused: bool,
+    score: ConsumerScore,
+}
+
+/// Reply to the `transport.consumeData` request: the SCTP stream parameters the worker assigned
+/// (when consuming over SCTP) plus the label/protocol inherited from the producer.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConsumeDataResponse {
+    sctp_stream_parameters: Option<SctpStreamParameters>,
+    label: String,
+    protocol: String,
+}
+
 pub(super) trait TransportImpl<Dump, Stat, RemoteParameters>:
     Transport<Dump, Stat, RemoteParameters>
 where
@@ -85,52 +159,100 @@ where
 
     fn channel(&self) -> &Channel;
 
+    fn payload_channel(&self) -> &PayloadChannel;
+
+    fn executor(&self) -> &Arc<Executor<'static>>;
+
     fn dump_impl<'a>(
         &'a self,
     ) -> Pin<Box<dyn Future<Output = Result<Dump, RequestError>> + Send + 'a>>
+    where
+        Dump: 'a,
+    {
+        self.dump_with_impl(RequestOptions::default())
+    }
+
+    /// Same as [`Self::dump_impl`], but the request fails with [`RequestError::TimedOut`] (or is
+    /// cancelled) according to `options` instead of waiting on the worker indefinitely.
+    fn dump_with_impl<'a>(
+        &'a self,
+        options: RequestOptions,
+    ) -> Pin<Box<dyn Future<Output = Result<Dump, RequestError>> + Send + 'a>>
     where
         Dump: 'a,
     {
         self.channel()
-            .request(TransportDumpRequest {
-                internal: TransportInternal {
-                    router_id: self.router_id(),
-                    transport_id: self.id(),
+            .request_with(
+                TransportDumpRequest {
+                    internal: TransportInternal {
+                        router_id: self.router_id(),
+                        transport_id: self.id(),
+                    },
+                    phantom_data: PhantomData {},
                 },
-                phantom_data: PhantomData {},
-            })
+                options,
+            )
             .boxed()
     }
 
     fn get_stats_impl<'a>(
         &'a self,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<Stat>, RequestError>> + Send + 'a>>
+    where
+        Stat: 'a,
+    {
+        self.get_stats_with_impl(RequestOptions::default())
+    }
+
+    /// Same as [`Self::get_stats_impl`], but bounded by `options`.
+    fn get_stats_with_impl<'a>(
+        &'a self,
+        options: RequestOptions,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Stat>, RequestError>> + Send + 'a>>
     where
         Stat: 'a,
     {
         self.channel()
-            .request(TransportGetStatsRequest {
-                internal: TransportInternal {
-                    router_id: self.router_id(),
-                    transport_id: self.id(),
+            .request_with(
+                TransportGetStatsRequest {
+                    internal: TransportInternal {
+                        router_id: self.router_id(),
+                        transport_id: self.id(),
+                    },
+                    phantom_data: PhantomData {},
                 },
-                phantom_data: PhantomData {},
-            })
+                options,
+            )
             .boxed()
     }
 
     fn set_max_incoming_bitrate_impl(
         &self,
         bitrate: u32,
+    ) -> Pin<Box<dyn Future<Output = Result<(), RequestError>> + Send + '_>> {
+        self.set_max_incoming_bitrate_with_impl(
+            bitrate,
+            RequestOptions::default().with_priority(RequestPriority::HIGH),
+        )
+    }
+
+    /// Same as [`Self::set_max_incoming_bitrate_impl`], but bounded by `options`.
+    fn set_max_incoming_bitrate_with_impl(
+        &self,
+        bitrate: u32,
+        options: RequestOptions,
     ) -> Pin<Box<dyn Future<Output = Result<(), RequestError>> + Send + '_>> {
         self.channel()
-            .request(TransportSetMaxIncomingBitrateRequest {
-                internal: TransportInternal {
-                    router_id: self.router_id(),
-                    transport_id: self.id(),
+            .request_with(
+                TransportSetMaxIncomingBitrateRequest {
+                    internal: TransportInternal {
+                        router_id: self.router_id(),
+                        transport_id: self.id(),
+                    },
+                    data: TransportSetMaxIncomingBitrateData { bitrate },
                 },
-                data: TransportSetMaxIncomingBitrateData { bitrate },
-            })
+                options,
+            )
             .boxed()
     }
 
@@ -140,4 +262,184 @@ where
     ) -> Pin<Box<dyn Future<Output = Result<Producer, RequestError>> + Send>> {
         todo!()
     }
+
+    /// Issues the `transport.consume` request carrying the producer id, RTP capabilities, initial
+    /// paused state and preferred layers, and constructs a [`Consumer`] bound to this transport's
+    /// `router_id`/`transport_id`. `kind`/`type`/`rtp_parameters` are the already-negotiated RTP
+    /// parameters for the new consumer, resolved by the caller ahead of time (the same
+    /// pre-negotiated-context convention [`Self::produce_data_impl`] uses for `DataProducerType`).
+    fn consume_impl(
+        &self,
+        kind: MediaKind,
+        r#type: ConsumerType,
+        rtp_parameters: RtpParameters,
+        consumer_options: ConsumerOptions,
+    ) -> Pin<Box<dyn Future<Output = Result<Consumer, RequestError>> + Send + '_>>
+    where
+        Self: crate::transport::TransportGeneric<Dump, Stat> + Clone + 'static,
+        Dump: 'static,
+        Stat: 'static,
+    {
+        Box::pin(async move {
+            let response: ConsumeResponse = self
+                .channel()
+                .request(TransportConsumeRequest {
+                    internal: TransportInternal {
+                        router_id: self.router_id(),
+                        transport_id: self.id(),
+                    },
+                    data: TransportConsumeData {
+                        producer_id: consumer_options.producer_id,
+                        rtp_capabilities: consumer_options.rtp_capabilities,
+                        paused: consumer_options.paused,
+                        preferred_layers: consumer_options.preferred_layers,
+                    },
+                })
+                .await?;
+
+            Ok(Consumer::new(
+                ConsumerId::new(),
+                consumer_options.producer_id,
+                kind,
+                r#type,
+                rtp_parameters,
+                response.paused,
+                Arc::clone(self.executor()),
+                self.channel().clone(),
+                self.payload_channel().clone(),
+                response.producer_paused,
+                response.score,
+                consumer_options.preferred_layers,
+                consumer_options.app_data,
+                self.clone(),
+            )
+            .await)
+        })
+    }
+
+    /// Issues the `transport.produceData` request and constructs a [`DataProducer`]. `r#type` is
+    /// the already-resolved kind of data producer (SCTP vs. direct), passed ahead of
+    /// `data_producer_options` rather than inferred from it, matching how concrete transports
+    /// (e.g. `WebRtcTransport::produce_data`) already call this method.
+    fn produce_data_impl(
+        &self,
+        r#type: DataProducerType,
+        data_producer_options: DataProducerOptions,
+    ) -> Pin<Box<dyn Future<Output = Result<DataProducer, RequestError>> + Send + '_>>
+    where
+        Self: crate::transport::TransportGeneric<Dump, Stat> + Clone + 'static,
+        Dump: 'static,
+        Stat: 'static,
+    {
+        Box::pin(async move {
+            let id = data_producer_options.id.unwrap_or_else(DataProducerId::new);
+
+            self.channel()
+                .request(TransportProduceDataRequest {
+                    internal: DataProducerInternal {
+                        router_id: self.router_id(),
+                        transport_id: self.id(),
+                        data_producer_id: id,
+                    },
+                    data: TransportProduceDataData {
+                        r#type: r#type.clone(),
+                        sctp_stream_parameters: data_producer_options.sctp_stream_parameters.clone(),
+                        label: data_producer_options.label.clone(),
+                        protocol: data_producer_options.protocol.clone(),
+                    },
+                })
+                .await?;
+
+            DataProducer::new(
+                id,
+                r#type.clone(),
+                data_producer_options.sctp_stream_parameters,
+                data_producer_options.label,
+                data_producer_options.protocol,
+                data_producer_options.buffered_amount_low_threshold,
+                data_producer_options.buffered_amount_high_water_mark,
+                data_producer_options.stats_poll_interval,
+                data_producer_options.subprotocol_registry,
+                Arc::clone(self.executor()),
+                self.channel().clone(),
+                self.payload_channel().clone(),
+                data_producer_options.app_data,
+                self.clone(),
+                matches!(r#type, DataProducerType::Direct),
+            )
+            .await
+            .map_err(|error| RequestError::Response {
+                reason: error.to_string(),
+            })
+        })
+    }
+
+    /// Issues the `transport.consumeData` request and constructs a [`DataConsumer`]. `r#type`
+    /// mirrors [`Self::produce_data_impl`]'s leading `r#type` parameter.
+    fn consume_data_impl(
+        &self,
+        r#type: DataConsumerType,
+        data_consumer_options: DataConsumerOptions,
+    ) -> Pin<Box<dyn Future<Output = Result<DataConsumer, RequestError>> + Send + '_>>
+    where
+        Self: crate::transport::Transport + Clone + 'static,
+    {
+        Box::pin(async move {
+            let id = DataConsumerId::new();
+
+            let response: ConsumeDataResponse = self
+                .channel()
+                .request(TransportConsumeDataRequest {
+                    internal: DataConsumerInternal {
+                        router_id: self.router_id(),
+                        transport_id: self.id(),
+                        data_consumer_id: id,
+                        data_producer_id: data_consumer_options.data_producer_id,
+                    },
+                    data: TransportConsumeDataData {
+                        r#type: r#type.clone(),
+                        sctp_stream_parameters: None,
+                        ordered: data_consumer_options.ordered,
+                        max_packet_life_time: data_consumer_options.max_packet_life_time,
+                        max_retransmits: data_consumer_options.max_retransmits,
+                    },
+                })
+                .await?;
+
+            Ok(DataConsumer::new(
+                id,
+                r#type,
+                response.sctp_stream_parameters,
+                response.label,
+                response.protocol,
+                data_consumer_options.data_producer_id,
+                Arc::clone(self.executor()),
+                self.channel().clone(),
+                self.payload_channel().clone(),
+                data_consumer_options.app_data,
+                Box::new(self.clone()),
+            )
+            .await)
+        })
+    }
+
+    /// Issues the `transport.enableTraceEvent` request. Concrete transport implementations
+    /// subscribe to the worker `Channel`'s notification stream keyed by their [`TransportId`] and
+    /// fan the decoded [`TransportTraceEventData`] out to listeners registered via `on_trace`.
+    fn enable_trace_event_impl(
+        &self,
+        types: &[TransportTraceEventType],
+    ) -> Pin<Box<dyn Future<Output = Result<(), RequestError>> + Send + '_>> {
+        self.channel()
+            .request(TransportEnableTraceEventRequest {
+                internal: TransportInternal {
+                    router_id: self.router_id(),
+                    transport_id: self.id(),
+                },
+                data: TransportEnableTraceEventData {
+                    types: types.to_vec(),
+                },
+            })
+            .boxed()
+    }
 }
@@ -10,6 +10,7 @@ use crate::messages::{
     TransportInternal, TransportRestartIceRequest, WebRtcTransportData,
 };
 use crate::producer::{Producer, ProducerId, ProducerOptions};
+use crate::router::stats::{RtcStats, RtcStatsReport};
 use crate::router::{Router, RouterId};
 use crate::sctp_parameters::{NumSctpStreams, SctpParameters};
 use crate::transport::{
@@ -17,17 +18,24 @@ use crate::transport::{
     RtpListener, SctpListener, Transport, TransportGeneric, TransportId, TransportImpl,
     TransportTraceEventData, TransportTraceEventType,
 };
+use crate::worker::channels::RequestPriority;
+use crate::worker::request_options::{RequestOptions, DEFAULT_REQUEST_TIMEOUT};
 use crate::worker::{Channel, RequestError, SubscriptionHandler};
+use async_channel::{Receiver, Sender};
 use async_executor::Executor;
 use async_trait::async_trait;
+use futures_lite::Stream;
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::mem;
 use std::ops::Deref;
+use std::pin::Pin;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
 use thiserror::Error;
 
 /// Struct that protects an invariant of having non-empty list of listen IPs
@@ -99,6 +107,9 @@ pub struct WebRtcTransportOptions {
     /// Maximum SCTP send buffer used by DataConsumers.
     /// Default 262144.
     pub sctp_send_buffer_size: u32,
+    /// How long `connect()` and `restart_ice()` wait for the worker's response before failing
+    /// with [`RequestError::TimedOut`]. Default 5 seconds.
+    pub request_timeout: Duration,
     /// Custom application data.
     pub app_data: AppData,
 }
@@ -116,6 +127,7 @@ impl WebRtcTransportOptions {
             num_sctp_streams: NumSctpStreams::default(),
             max_sctp_message_size: 262144,
             sctp_send_buffer_size: 262144,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
             app_data: AppData::default(),
         }
     }
@@ -189,6 +201,88 @@ pub struct WebRtcTransportRemoteParameters {
     pub dtls_parameters: DtlsParameters,
 }
 
+/// O(1) allocator for SCTP stream ids in `0..mis`: a bitset answers whether an id is currently
+/// in use, while a stack of free ids means `allocate()` never has to scan for one.
+#[derive(Debug, Default)]
+struct SctpStreamIdAllocator {
+    used: Vec<bool>,
+    free: Vec<u16>,
+}
+
+impl SctpStreamIdAllocator {
+    fn new(mis: u16) -> Self {
+        Self {
+            used: vec![false; usize::from(mis)],
+            free: (0..mis).rev().collect(),
+        }
+    }
+
+    fn allocate(&mut self) -> Option<u16> {
+        let sctp_stream_id = self.free.pop()?;
+        self.used[usize::from(sctp_stream_id)] = true;
+        Some(sctp_stream_id)
+    }
+
+    fn deallocate(&mut self, sctp_stream_id: u16) {
+        if let Some(used) = self.used.get_mut(usize::from(sctp_stream_id)) {
+            if mem::replace(used, false) {
+                self.free.push(sctp_stream_id);
+            }
+        }
+    }
+}
+
+/// OpenTelemetry instrumentation for [`WebRtcTransport`] operations and notifications, kept
+/// behind the `telemetry` feature so non-telemetry builds don't pull in the dependency.
+#[cfg(feature = "telemetry")]
+mod telemetry {
+    use super::{RouterId, TransportId};
+    use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
+    use opentelemetry::{global, KeyValue};
+
+    /// Starts a span for a `WebRtcTransport` operation, tagged with its transport and router ids.
+    pub(super) fn start_span(
+        name: &'static str,
+        transport_id: TransportId,
+        router_id: RouterId,
+    ) -> global::BoxedSpan {
+        let tracer = global::tracer("mediasoup");
+        tracer
+            .span_builder(name)
+            .with_kind(SpanKind::Client)
+            .with_attributes(vec![
+                KeyValue::new("transport_id", transport_id.to_string()),
+                KeyValue::new("router_id", router_id.to_string()),
+            ])
+            .start(&tracer)
+    }
+
+    /// Marks `span` with an error status carrying `result`'s error message, if any.
+    pub(super) fn record_result<T, E: std::fmt::Display>(
+        span: &mut global::BoxedSpan,
+        result: &Result<T, E>,
+    ) {
+        if let Err(error) = result {
+            span.set_status(Status::error(error.to_string()));
+        }
+    }
+
+    /// Records a transport notification as an event on a short-lived span, since notification
+    /// dispatch happens outside of any request span.
+    pub(super) fn record_notification_event(
+        transport_id: TransportId,
+        event_name: &'static str,
+        attributes: Vec<KeyValue>,
+    ) {
+        let tracer = global::tracer("mediasoup");
+        let mut span = tracer
+            .span_builder("webrtc_transport.notification")
+            .with_attributes(vec![KeyValue::new("transport_id", transport_id.to_string())])
+            .start(&tracer);
+        span.add_event(event_name, attributes);
+    }
+}
+
 #[derive(Default)]
 struct Handlers {
     new_producer: Mutex<Vec<Box<dyn Fn(&Producer) + Send>>>,
@@ -201,6 +295,35 @@ struct Handlers {
     sctp_state_change: Mutex<Vec<Box<dyn Fn(SctpState) + Send>>>,
     trace: Mutex<Vec<Box<dyn Fn(&TransportTraceEventData) + Send>>>,
     closed: Mutex<Vec<Box<dyn FnOnce() + Send>>>,
+    // Subscribers of the `*_stream()` family; pruned lazily as sends to dropped receivers fail.
+    ice_state_change_broadcast: Mutex<Vec<Sender<IceState>>>,
+    ice_selected_tuple_change_broadcast: Mutex<Vec<Sender<TransportTuple>>>,
+    dtls_state_change_broadcast: Mutex<Vec<Sender<DtlsState>>>,
+    sctp_state_change_broadcast: Mutex<Vec<Sender<SctpState>>>,
+    trace_broadcast: Mutex<Vec<Sender<TransportTraceEventData>>>,
+}
+
+/// An async `Stream` of transport events returned by the `*_stream()` family, e.g.
+/// [`WebRtcTransport::ice_state_change_stream`]. Backed by an unbounded channel that the
+/// notification dispatcher in [`WebRtcTransport::new`] fans out to alongside the `connect_*`
+/// callback vectors; the subscription is dropped automatically once this stream is dropped.
+pub struct EventStream<T> {
+    receiver: Receiver<T>,
+}
+
+impl<T> Stream for EventStream<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().receiver).poll_next(cx)
+    }
+}
+
+/// Registers a new subscriber in `senders` and returns the `Stream` it will receive events on.
+fn broadcast_stream<T>(senders: &Mutex<Vec<Sender<T>>>) -> EventStream<T> {
+    let (sender, receiver) = async_channel::unbounded();
+    senders.lock().unwrap().push(sender);
+    EventStream { receiver }
 }
 
 #[derive(Debug, Deserialize)]
@@ -229,7 +352,8 @@ enum Notification {
 struct Inner {
     id: TransportId,
     next_mid_for_consumers: AtomicUsize,
-    used_sctp_stream_ids: Mutex<HashMap<u16, bool>>,
+    used_sctp_stream_ids: Mutex<SctpStreamIdAllocator>,
+    request_timeout: Duration,
     executor: Arc<Executor<'static>>,
     channel: Channel,
     payload_channel: Channel,
@@ -304,7 +428,16 @@ impl Transport for WebRtcTransport {
     async fn produce(&self, producer_options: ProducerOptions) -> Result<Producer, ProduceError> {
         debug!("produce()");
 
-        let producer = self.produce_impl(producer_options).await?;
+        #[cfg(feature = "telemetry")]
+        let mut span =
+            telemetry::start_span("webrtc_transport.produce", self.id(), self.router_id());
+
+        let result = self.produce_impl(producer_options).await;
+
+        #[cfg(feature = "telemetry")]
+        telemetry::record_result(&mut span, &result);
+
+        let producer = result?;
 
         for callback in self.inner.handlers.new_producer.lock().unwrap().iter() {
             callback(&producer);
@@ -319,7 +452,16 @@ impl Transport for WebRtcTransport {
     async fn consume(&self, consumer_options: ConsumerOptions) -> Result<Consumer, ConsumeError> {
         debug!("consume()");
 
-        let consumer = self.consume_impl(consumer_options).await?;
+        #[cfg(feature = "telemetry")]
+        let mut span =
+            telemetry::start_span("webrtc_transport.consume", self.id(), self.router_id());
+
+        let result = self.consume_impl(consumer_options).await;
+
+        #[cfg(feature = "telemetry")]
+        telemetry::record_result(&mut span, &result);
+
+        let consumer = result?;
 
         for callback in self.inner.handlers.new_consumer.lock().unwrap().iter() {
             callback(&consumer);
@@ -337,9 +479,18 @@ impl Transport for WebRtcTransport {
     ) -> Result<DataProducer, ProduceDataError> {
         debug!("produce_data()");
 
-        let data_producer = self
+        #[cfg(feature = "telemetry")]
+        let mut span =
+            telemetry::start_span("webrtc_transport.produce_data", self.id(), self.router_id());
+
+        let result = self
             .produce_data_impl(DataProducerType::Sctp, data_producer_options)
-            .await?;
+            .await;
+
+        #[cfg(feature = "telemetry")]
+        telemetry::record_result(&mut span, &result);
+
+        let data_producer = result?;
 
         for callback in self.inner.handlers.new_data_producer.lock().unwrap().iter() {
             callback(&data_producer);
@@ -357,9 +508,18 @@ impl Transport for WebRtcTransport {
     ) -> Result<DataConsumer, ConsumeDataError> {
         debug!("consume_data()");
 
-        let data_consumer = self
+        #[cfg(feature = "telemetry")]
+        let mut span =
+            telemetry::start_span("webrtc_transport.consume_data", self.id(), self.router_id());
+
+        let result = self
             .consume_data_impl(DataConsumerType::Sctp, data_consumer_options)
-            .await?;
+            .await;
+
+        #[cfg(feature = "telemetry")]
+        telemetry::record_result(&mut span, &result);
+
+        let data_consumer = result?;
 
         for callback in self.inner.handlers.new_data_consumer.lock().unwrap().iter() {
             callback(&data_consumer);
@@ -376,14 +536,32 @@ impl TransportGeneric<WebRtcTransportDump, WebRtcTransportStat> for WebRtcTransp
     async fn dump(&self) -> Result<WebRtcTransportDump, RequestError> {
         debug!("dump()");
 
-        self.dump_impl().await
+        #[cfg(feature = "telemetry")]
+        let mut span =
+            telemetry::start_span("webrtc_transport.dump", self.id(), self.router_id());
+
+        let result = self.dump_impl().await;
+
+        #[cfg(feature = "telemetry")]
+        telemetry::record_result(&mut span, &result);
+
+        result
     }
 
     /// Get Transport stats.
     async fn get_stats(&self) -> Result<Vec<WebRtcTransportStat>, RequestError> {
         debug!("get_stats()");
 
-        self.get_stats_impl().await
+        #[cfg(feature = "telemetry")]
+        let mut span =
+            telemetry::start_span("webrtc_transport.get_stats", self.id(), self.router_id());
+
+        let result = self.get_stats_impl().await;
+
+        #[cfg(feature = "telemetry")]
+        telemetry::record_result(&mut span, &result);
+
+        result
     }
 
     async fn enable_trace_event(
@@ -474,23 +652,15 @@ impl TransportImpl<WebRtcTransportDump, WebRtcTransportStat> for WebRtcTransport
     }
 
     fn allocate_sctp_stream_id(&self) -> Option<u16> {
-        let mut used_sctp_stream_ids = self.inner.used_sctp_stream_ids.lock().unwrap();
-        // This is simple, but not the fastest implementation, maybe worth improving
-        for (index, used) in used_sctp_stream_ids.iter_mut() {
-            if !*used {
-                *used = true;
-                return Some(*index);
-            }
-        }
-
-        None
+        self.inner.used_sctp_stream_ids.lock().unwrap().allocate()
     }
 
     fn deallocate_sctp_stream_id(&self, sctp_stream_id: u16) {
-        let mut used_sctp_stream_ids = self.inner.used_sctp_stream_ids.lock().unwrap();
-        if let Some(used) = used_sctp_stream_ids.get_mut(&sctp_stream_id) {
-            *used = false;
-        }
+        self.inner
+            .used_sctp_stream_ids
+            .lock()
+            .unwrap()
+            .deallocate(sctp_stream_id);
     }
 }
 
@@ -503,6 +673,7 @@ impl WebRtcTransport {
         data: WebRtcTransportData,
         app_data: AppData,
         router: Router,
+        request_timeout: Duration,
     ) -> Self {
         debug!("new()");
 
@@ -522,6 +693,20 @@ impl WebRtcTransport {
                                 for callback in handlers.ice_state_change.lock().unwrap().iter() {
                                     callback(ice_state);
                                 }
+                                handlers
+                                    .ice_state_change_broadcast
+                                    .lock()
+                                    .unwrap()
+                                    .retain(|sender| sender.try_send(ice_state).is_ok());
+                                #[cfg(feature = "telemetry")]
+                                telemetry::record_notification_event(
+                                    id,
+                                    "ice_state_change",
+                                    vec![opentelemetry::KeyValue::new(
+                                        "ice_state",
+                                        format!("{:?}", ice_state),
+                                    )],
+                                );
                             }
                             Notification::IceSelectedTupleChange { ice_selected_tuple } => {
                                 data.ice_selected_tuple
@@ -533,6 +718,13 @@ impl WebRtcTransport {
                                 {
                                     callback(&ice_selected_tuple);
                                 }
+                                handlers
+                                    .ice_selected_tuple_change_broadcast
+                                    .lock()
+                                    .unwrap()
+                                    .retain(|sender| {
+                                        sender.try_send(ice_selected_tuple.clone()).is_ok()
+                                    });
                             }
                             Notification::DtlsStateChange {
                                 dtls_state,
@@ -550,6 +742,20 @@ impl WebRtcTransport {
                                 for callback in handlers.dtls_state_change.lock().unwrap().iter() {
                                     callback(dtls_state);
                                 }
+                                handlers
+                                    .dtls_state_change_broadcast
+                                    .lock()
+                                    .unwrap()
+                                    .retain(|sender| sender.try_send(dtls_state).is_ok());
+                                #[cfg(feature = "telemetry")]
+                                telemetry::record_notification_event(
+                                    id,
+                                    "dtls_state_change",
+                                    vec![opentelemetry::KeyValue::new(
+                                        "dtls_state",
+                                        format!("{:?}", dtls_state),
+                                    )],
+                                );
                             }
                             Notification::SctpStateChange { sctp_state } => {
                                 data.sctp_state.lock().unwrap().replace(sctp_state);
@@ -557,11 +763,37 @@ impl WebRtcTransport {
                                 for callback in handlers.sctp_state_change.lock().unwrap().iter() {
                                     callback(sctp_state);
                                 }
+                                handlers
+                                    .sctp_state_change_broadcast
+                                    .lock()
+                                    .unwrap()
+                                    .retain(|sender| sender.try_send(sctp_state).is_ok());
+                                #[cfg(feature = "telemetry")]
+                                telemetry::record_notification_event(
+                                    id,
+                                    "sctp_state_change",
+                                    vec![opentelemetry::KeyValue::new(
+                                        "sctp_state",
+                                        format!("{:?}", sctp_state),
+                                    )],
+                                );
                             }
                             Notification::Trace(trace_event_data) => {
                                 for callback in handlers.trace.lock().unwrap().iter() {
                                     callback(&trace_event_data);
                                 }
+                                handlers.trace_broadcast.lock().unwrap().retain(|sender| {
+                                    sender.try_send(trace_event_data.clone()).is_ok()
+                                });
+                                #[cfg(feature = "telemetry")]
+                                telemetry::record_notification_event(
+                                    id,
+                                    "trace",
+                                    vec![opentelemetry::KeyValue::new(
+                                        "trace_type",
+                                        format!("{:?}", trace_event_data),
+                                    )],
+                                );
                             }
                         },
                         Err(error) => {
@@ -574,19 +806,16 @@ impl WebRtcTransport {
         };
 
         let next_mid_for_consumers = AtomicUsize::default();
-        let used_sctp_stream_ids = Mutex::new({
-            let mut used_used_sctp_stream_ids = HashMap::new();
-            if let Some(sctp_parameters) = &data.sctp_parameters {
-                for i in 0..sctp_parameters.mis {
-                    used_used_sctp_stream_ids.insert(i, false);
-                }
-            }
-            used_used_sctp_stream_ids
-        });
+        let used_sctp_stream_ids = Mutex::new(SctpStreamIdAllocator::new(
+            data.sctp_parameters
+                .as_ref()
+                .map_or(0, |sctp_parameters| sctp_parameters.mis),
+        ));
         let inner = Arc::new(Inner {
             id,
             next_mid_for_consumers,
             used_sctp_stream_ids,
+            request_timeout,
             executor,
             channel,
             payload_channel,
@@ -607,16 +836,29 @@ impl WebRtcTransport {
     ) -> Result<(), RequestError> {
         debug!("connect()");
 
-        let response = self
+        #[cfg(feature = "telemetry")]
+        let mut span =
+            telemetry::start_span("webrtc_transport.connect", self.id(), self.router_id());
+
+        let result = self
             .inner
             .channel
-            .request(TransportConnectRequestWebRtc {
-                internal: self.get_internal(),
-                data: TransportConnectRequestWebRtcData {
-                    dtls_parameters: remote_parameters.dtls_parameters,
+            .request_with(
+                TransportConnectRequestWebRtc {
+                    internal: self.get_internal(),
+                    data: TransportConnectRequestWebRtcData {
+                        dtls_parameters: remote_parameters.dtls_parameters,
+                    },
                 },
-            })
-            .await?;
+                RequestOptions::with_timeout(self.inner.request_timeout)
+                    .with_priority(RequestPriority::HIGH),
+            )
+            .await;
+
+        #[cfg(feature = "telemetry")]
+        telemetry::record_result(&mut span, &result);
+
+        let response = result?;
 
         self.inner.data.dtls_parameters.lock().unwrap().role = response.dtls_local_role;
 
@@ -677,15 +919,123 @@ impl WebRtcTransport {
     pub async fn restart_ice(&self) -> Result<IceParameters, RequestError> {
         debug!("restart_ice()");
 
-        let response = self
+        #[cfg(feature = "telemetry")]
+        let mut span =
+            telemetry::start_span("webrtc_transport.restart_ice", self.id(), self.router_id());
+
+        let result = self
             .inner
             .channel
-            .request(TransportRestartIceRequest {
-                internal: self.get_internal(),
-            })
-            .await?;
+            .request_with(
+                TransportRestartIceRequest {
+                    internal: self.get_internal(),
+                },
+                RequestOptions::with_timeout(self.inner.request_timeout)
+                    .with_priority(RequestPriority::HIGH),
+            )
+            .await;
+
+        #[cfg(feature = "telemetry")]
+        telemetry::record_result(&mut span, &result);
+
+        Ok(result?.ice_parameters)
+    }
+
+    /// Returns current RTC statistics of the transport mapped onto the standardized W3C WebRTC
+    /// Statistics dictionaries, so server-side numbers line up 1:1 with what a browser's
+    /// `RTCPeerConnection.getStats()` produces.
+    pub async fn get_standard_stats(&self) -> Result<RtcStatsReport, RequestError> {
+        debug!("get_standard_stats()");
+
+        let stats = self.get_stats().await?;
+        let transport_stat = stats.into_iter().next();
+
+        let mut report = RtcStatsReport::new();
+
+        let timestamp = transport_stat
+            .as_ref()
+            .map_or(0.0, |stat| stat.timestamp as f64);
+        let bytes_sent = transport_stat
+            .as_ref()
+            .map_or(0, |stat| stat.bytes_sent as u64);
+        let bytes_received = transport_stat
+            .as_ref()
+            .map_or(0, |stat| stat.bytes_received as u64);
+        let available_outgoing_bitrate = transport_stat
+            .as_ref()
+            .and_then(|stat| stat.available_outgoing_bitrate)
+            .map(u64::from);
+        let available_incoming_bitrate = transport_stat
+            .as_ref()
+            .and_then(|stat| stat.available_incoming_bitrate)
+            .map(u64::from);
+
+        let transport_id = format!("transport-{}", self.id());
+        let candidate_pair_id = format!("candidate-pair-{}", self.id());
+        let local_candidate_id = format!("local-candidate-{}", self.id());
+        let remote_candidate_id = format!("remote-candidate-{}", self.id());
+
+        let ice_selected_tuple = self.ice_selected_tuple();
+
+        report.insert(
+            transport_id,
+            RtcStats::Transport {
+                timestamp,
+                bytes_sent,
+                bytes_received,
+                available_outgoing_bitrate,
+                available_incoming_bitrate,
+                dtls_state: format!("{:?}", self.dtls_state()).to_lowercase(),
+                ice_role: format!("{:?}", self.ice_role()).to_lowercase(),
+                selected_candidate_pair_id: ice_selected_tuple
+                    .is_some()
+                    .then(|| candidate_pair_id.clone()),
+            },
+        );
+
+        if let Some(tuple) = ice_selected_tuple {
+            let state = match self.ice_state() {
+                IceState::Completed | IceState::Connected => "succeeded",
+                IceState::Disconnected | IceState::Closed => "failed",
+                IceState::New | IceState::Connecting => "in-progress",
+            }
+            .to_string();
+
+            report.insert(
+                candidate_pair_id,
+                RtcStats::CandidatePair {
+                    timestamp,
+                    state,
+                    bytes_sent,
+                    bytes_received,
+                    local_candidate_id: local_candidate_id.clone(),
+                    remote_candidate_id: remote_candidate_id.clone(),
+                    available_outgoing_bitrate,
+                },
+            );
+
+            report.insert(
+                local_candidate_id,
+                RtcStats::LocalCandidate {
+                    timestamp,
+                    ip: tuple.local_ip.clone(),
+                    port: tuple.local_port,
+                    protocol: tuple.protocol.clone(),
+                },
+            );
+
+            report.insert(
+                remote_candidate_id,
+                RtcStats::RemoteCandidate {
+                    timestamp,
+                    ip: tuple.remote_ip.clone(),
+                    port: tuple.remote_port,
+                    protocol: tuple.protocol,
+                },
+            );
+        }
 
-        Ok(response.ice_parameters)
+        Ok(report)
     }
 
     pub fn connect_ice_state_change<F: Fn(IceState) + Send + 'static>(&self, callback: F) {
@@ -697,6 +1047,13 @@ impl WebRtcTransport {
             .push(Box::new(callback));
     }
 
+    /// Returns a `Stream` of ICE state changes, as an alternative to
+    /// [`Self::connect_ice_state_change`] for async consumers that want to `select!` over
+    /// transport events instead of stashing state in a callback.
+    pub fn ice_state_change_stream(&self) -> impl Stream<Item = IceState> {
+        broadcast_stream(&self.inner.handlers.ice_state_change_broadcast)
+    }
+
     pub fn connect_ice_selected_tuple_change<F: Fn(&TransportTuple) + Send + 'static>(
         &self,
         callback: F,
@@ -709,6 +1066,12 @@ impl WebRtcTransport {
             .push(Box::new(callback));
     }
 
+    /// Returns a `Stream` of ICE selected tuple changes, as an alternative to
+    /// [`Self::connect_ice_selected_tuple_change`].
+    pub fn ice_selected_tuple_change_stream(&self) -> impl Stream<Item = TransportTuple> {
+        broadcast_stream(&self.inner.handlers.ice_selected_tuple_change_broadcast)
+    }
+
     pub fn connect_dtls_state_change<F: Fn(DtlsState) + Send + 'static>(&self, callback: F) {
         self.inner
             .handlers
@@ -718,6 +1081,12 @@ impl WebRtcTransport {
             .push(Box::new(callback));
     }
 
+    /// Returns a `Stream` of DTLS state changes, as an alternative to
+    /// [`Self::connect_dtls_state_change`].
+    pub fn dtls_state_change_stream(&self) -> impl Stream<Item = DtlsState> {
+        broadcast_stream(&self.inner.handlers.dtls_state_change_broadcast)
+    }
+
     pub fn connect_sctp_state_change<F: Fn(SctpState) + Send + 'static>(&self, callback: F) {
         self.inner
             .handlers
@@ -727,6 +1096,18 @@ impl WebRtcTransport {
             .push(Box::new(callback));
     }
 
+    /// Returns a `Stream` of SCTP state changes, as an alternative to
+    /// [`Self::connect_sctp_state_change`].
+    pub fn sctp_state_change_stream(&self) -> impl Stream<Item = SctpState> {
+        broadcast_stream(&self.inner.handlers.sctp_state_change_broadcast)
+    }
+
+    /// Returns a `Stream` of trace events, as an alternative to
+    /// [`TransportGeneric::connect_trace`](crate::transport::TransportGeneric::connect_trace).
+    pub fn trace_stream(&self) -> impl Stream<Item = TransportTraceEventData> {
+        broadcast_stream(&self.inner.handlers.trace_broadcast)
+    }
+
     fn get_internal(&self) -> TransportInternal {
         TransportInternal {
             router_id: self.router().id(),
@@ -734,3 +1115,66 @@ impl WebRtcTransport {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SctpStreamIdAllocator;
+
+    #[test]
+    fn allocates_ids_in_range_and_reports_exhaustion() {
+        let mut allocator = SctpStreamIdAllocator::new(3);
+
+        let mut allocated = vec![
+            allocator.allocate().unwrap(),
+            allocator.allocate().unwrap(),
+            allocator.allocate().unwrap(),
+        ];
+        allocated.sort_unstable();
+        assert_eq!(allocated, vec![0, 1, 2]);
+
+        assert_eq!(allocator.allocate(), None);
+    }
+
+    #[test]
+    fn deallocated_id_becomes_available_again() {
+        let mut allocator = SctpStreamIdAllocator::new(1);
+
+        let id = allocator.allocate().unwrap();
+        assert_eq!(allocator.allocate(), None);
+
+        allocator.deallocate(id);
+        assert_eq!(allocator.allocate(), Some(id));
+    }
+
+    #[test]
+    fn deallocating_an_id_twice_does_not_double_free_it() {
+        let mut allocator = SctpStreamIdAllocator::new(1);
+
+        let id = allocator.allocate().unwrap();
+        allocator.deallocate(id);
+        allocator.deallocate(id);
+
+        let first = allocator.allocate();
+        assert_eq!(first, Some(id));
+        assert_eq!(allocator.allocate(), None);
+    }
+
+    #[test]
+    fn deallocating_an_id_that_was_never_allocated_is_a_no_op() {
+        let mut allocator = SctpStreamIdAllocator::new(2);
+
+        allocator.deallocate(1);
+
+        let mut allocated = vec![allocator.allocate().unwrap(), allocator.allocate().unwrap()];
+        allocated.sort_unstable();
+        assert_eq!(allocated, vec![0, 1]);
+        assert_eq!(allocator.allocate(), None);
+    }
+
+    #[test]
+    fn zero_capacity_allocator_never_hands_out_an_id() {
+        let mut allocator = SctpStreamIdAllocator::new(0);
+
+        assert_eq!(allocator.allocate(), None);
+    }
+}
@@ -2,24 +2,292 @@
 use async_channel::{Receiver, Sender};
 use async_executor::Executor;
 use async_fs::File as AsyncFile;
+#[cfg(unix)]
 use async_process::unix::CommandExt;
 use async_process::Command;
+use bytes::{Bytes, BytesMut};
 use futures_lite::io::BufReader;
 use futures_lite::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+use log::error;
+#[cfg(unix)]
 use nix::unistd;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use std::error::Error;
+#[cfg(unix)]
 use std::fs::File as StdFile;
 use std::io;
+#[cfg(unix)]
 use std::os::raw::c_int;
+#[cfg(unix)]
 use std::os::unix::io::FromRawFd;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
 
-// netstring length for a 4194304 bytes payload.
-const NS_PAYLOAD_MAX_LEN: usize = 4194304;
+// Initial capacity of the reader task's accumulating buffer; it grows on demand (see
+// `create_channel_pair`) so this is just a reasonable starting point, not a hard cap.
+const NS_PAYLOAD_INITIAL_CAPACITY: usize = 64 * 1024;
+
+// A message larger than this takes turns with its same-priority peers instead of monopolizing
+// the wire: it is split into chunks of this size and each chunk gets its own turn in the
+// round-robin rotation of its priority class.
+const ROUND_ROBIN_CHUNK_SIZE: usize = 0x4000;
+
+/// A queued request's priority class, determining the order in which [`create_channel_pair`]'s
+/// send loop drains it: high-priority control requests are always sent ahead of normal ones,
+/// which are in turn sent ahead of background bulk payloads. Only outgoing messages are queued
+/// by priority; replies from the worker are read and matched to their requests in arrival order.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RequestPriority(u8);
+
+impl RequestPriority {
+    /// Control operations that must never queue behind bulk data, e.g. `connect`, `restartIce`,
+    /// `setMaxIncomingBitrate`.
+    pub const HIGH: RequestPriority = RequestPriority(0x20);
+    /// Default priority for ordinary requests.
+    pub const NORMAL: RequestPriority = RequestPriority(0x40);
+    /// Bulk payloads, e.g. DataProducer/DataConsumer message contents, that should yield to
+    /// control traffic.
+    pub const BACKGROUND: RequestPriority = RequestPriority(0x80);
+}
+
+impl Default for RequestPriority {
+    fn default() -> Self {
+        RequestPriority::NORMAL
+    }
+}
+
+/// Ties together requests that must reach the worker in the order they were enqueued — e.g.
+/// produce-then-resume — even when they don't share a [`RequestPriority`] and would otherwise be
+/// free to be reordered by [`pop_next_chunk`]. `stream_id` identifies the related sequence of
+/// requests and `seq` their position within it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct OrderTag {
+    stream_id: u64,
+    seq: u64,
+}
+
+impl OrderTag {
+    pub fn new(stream_id: u64, seq: u64) -> Self {
+        Self { stream_id, seq }
+    }
+}
+
+/// Per-`stream_id` set of [`OrderTag::seq`] values still queued or partway through being sent.
+/// Only the smallest one for a given stream is eligible to have its next chunk popped, which is
+/// what keeps same-tag requests emitted in sequence order regardless of priority.
+type PendingSeqs = HashMap<u64, BTreeSet<u64>>;
+
+/// A queued request, split into round-robin chunks, along with the [`OrderTag`] (if any) it must
+/// stay in sequence with.
+struct QueuedMessage {
+    order_tag: Option<OrderTag>,
+    chunks: VecDeque<Vec<u8>>,
+}
+
+/// Splits `payload` into [`ROUND_ROBIN_CHUNK_SIZE`]-sized pieces so that a single large message
+/// takes turns with its same-priority peers instead of starving them until it is fully sent.
+fn chunk_payload(order_tag: Option<OrderTag>, payload: Vec<u8>) -> QueuedMessage {
+    let chunks = if payload.len() <= ROUND_ROBIN_CHUNK_SIZE {
+        VecDeque::from([payload])
+    } else {
+        payload.chunks(ROUND_ROBIN_CHUNK_SIZE).map(Vec::from).collect()
+    };
+
+    QueuedMessage { order_tag, chunks }
+}
+
+/// Whether `message` is allowed to have its next chunk sent: untagged messages always are, while
+/// a tagged one only is once it's the oldest still-pending message for its `stream_id`.
+fn is_ready(message: &QueuedMessage, pending: &PendingSeqs) -> bool {
+    match message.order_tag {
+        None => true,
+        Some(OrderTag { stream_id, seq }) => pending
+            .get(&stream_id)
+            .and_then(|seqs| seqs.iter().next())
+            .map_or(true, |&min_seq| min_seq == seq),
+    }
+}
+
+/// Picks the highest-priority queue containing an [`is_ready`] message and pops one chunk from
+/// it, rotating that message to the back of its queue if further chunks remain, or clearing its
+/// `seq` from `pending` once it's been sent in full. A not-ready message never blocks a ready one
+/// queued behind it in the same priority class: each queue is scanned for its first ready message
+/// rather than just checking the front, so e.g. an `OrderTag`-blocked HIGH request waiting on a
+/// predecessor can't stall unrelated, ready HIGH requests queued after it.
+fn pop_next_chunk(
+    high_queue: &mut VecDeque<QueuedMessage>,
+    normal_queue: &mut VecDeque<QueuedMessage>,
+    background_queue: &mut VecDeque<QueuedMessage>,
+    pending: &mut PendingSeqs,
+) -> Option<Vec<u8>> {
+    for queue in [high_queue, normal_queue, background_queue] {
+        let ready_index = queue.iter().position(|message| is_ready(message, pending));
+
+        let Some(ready_index) = ready_index else {
+            continue;
+        };
+
+        let mut message = queue
+            .remove(ready_index)
+            .expect("just matched Some above");
+        let chunk = message.chunks.pop_front();
+
+        if message.chunks.is_empty() {
+            if let Some(OrderTag { stream_id, seq }) = message.order_tag {
+                if let Some(seqs) = pending.get_mut(&stream_id) {
+                    seqs.remove(&seq);
+                }
+            }
+        } else {
+            queue.push_back(message);
+        }
+
+        return chunk;
+    }
+
+    None
+}
+
+/// Handle for queuing an outgoing message onto a [`create_channel_pair`] send loop under a given
+/// [`RequestPriority`] and, optionally, [`OrderTag`].
+#[derive(Clone)]
+pub struct PrioritizedSender {
+    high: Sender<(Option<OrderTag>, Vec<u8>)>,
+    normal: Sender<(Option<OrderTag>, Vec<u8>)>,
+    background: Sender<(Option<OrderTag>, Vec<u8>)>,
+}
+
+impl PrioritizedSender {
+    /// Queues `payload` for sending under `priority`, tied to `order_tag` if given.
+    pub async fn send(
+        &self,
+        priority: RequestPriority,
+        order_tag: Option<OrderTag>,
+        payload: Vec<u8>,
+    ) -> Result<(), async_channel::SendError<(Option<OrderTag>, Vec<u8>)>> {
+        let item = (order_tag, payload);
+        match priority {
+            RequestPriority::HIGH => self.high.send(item).await,
+            RequestPriority::NORMAL => self.normal.send(item).await,
+            _ => self.background.send(item).await,
+        }
+    }
+
+    /// Queues `payload` for sending at [`RequestPriority::NORMAL`], with no [`OrderTag`].
+    pub async fn send_default(
+        &self,
+        payload: Vec<u8>,
+    ) -> Result<(), async_channel::SendError<(Option<OrderTag>, Vec<u8>)>> {
+        self.send(RequestPriority::default(), None, payload).await
+    }
+}
+
+/// Error completing a request submitted via [`RequestSender::request`].
+#[derive(Debug, thiserror::Error)]
+pub enum ChannelRequestError {
+    /// A later request reused this request's `id` before a response for this one arrived (the
+    /// `id` counter wrapped around while this request was still in flight).
+    #[error("request interrupted by id collision")]
+    Interrupted,
+    /// The channel's reader task exited (worker process gone) before a response arrived.
+    #[error("channel closed before response arrived")]
+    Closed,
+}
+
+/// Removes a request's `inflight` entry when dropped, so a request abandoned before the reader
+/// task can deliver a reply — e.g. `Channel::request_with` losing a timeout/cancellation race —
+/// doesn't leave a stale entry behind for a later request to collide with once `id` wraps around.
+struct InflightGuard {
+    id: u32,
+    inflight: Arc<Mutex<HashMap<u32, Sender<Result<ChannelMessage, ChannelRequestError>>>>>,
+}
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        self.inflight.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Multiplexes concurrent requests over a single [`PrioritizedSender`], matching each outgoing
+/// request to its worker reply by an embedded `id` instead of serializing requests one at a time
+/// behind a single in-flight slot.
+#[derive(Clone)]
+pub struct RequestSender {
+    sender: PrioritizedSender,
+    next_id: Arc<AtomicU32>,
+    inflight: Arc<Mutex<HashMap<u32, Sender<Result<ChannelMessage, ChannelRequestError>>>>>,
+}
+
+impl RequestSender {
+    /// Sends `body` (a JSON request object, without an `id` field of its own) under `priority`,
+    /// tied to `order_tag` if given, and waits for the worker's matching reply.
+    pub async fn request(
+        &self,
+        priority: RequestPriority,
+        order_tag: Option<OrderTag>,
+        mut body: Value,
+    ) -> Result<ChannelMessage, ChannelRequestError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        body["id"] = Value::from(id);
+
+        let payload = serde_json::to_vec(&body).expect("request body is always valid JSON");
+
+        let (response_sender, response_receiver) = async_channel::bounded(1);
+
+        if let Some(previous) = self.inflight.lock().unwrap().insert(id, response_sender) {
+            // `id` wrapped around onto one that's still outstanding; let its waiter know instead
+            // of leaving it hanging forever.
+            let _ = previous.try_send(Err(ChannelRequestError::Interrupted));
+        }
+
+        let _guard = InflightGuard {
+            id,
+            inflight: Arc::clone(&self.inflight),
+        };
+
+        if self.sender.send(priority, order_tag, payload).await.is_err() {
+            return Err(ChannelRequestError::Closed);
+        }
+
+        response_receiver
+            .recv()
+            .await
+            .unwrap_or(Err(ChannelRequestError::Closed))
+    }
+
+    /// Sends `body` (a JSON object) under `priority`, tied to `order_tag` if given, without
+    /// waiting for or expecting a reply. Used for fire-and-forget notifications, e.g. the payload
+    /// channel's message sends.
+    pub async fn notify(
+        &self,
+        priority: RequestPriority,
+        order_tag: Option<OrderTag>,
+        body: Value,
+    ) -> Result<(), ChannelRequestError> {
+        let payload = serde_json::to_vec(&body).expect("notification body is always valid JSON");
+        self.notify_raw(priority, order_tag, payload).await
+    }
+
+    /// Same as [`Self::notify`], but for a raw, already-encoded payload, e.g. the binary frame
+    /// that follows a payload channel notification's JSON header.
+    pub async fn notify_raw(
+        &self,
+        priority: RequestPriority,
+        order_tag: Option<OrderTag>,
+        payload: Vec<u8>,
+    ) -> Result<(), ChannelRequestError> {
+        self.sender
+            .send(priority, order_tag, payload)
+            .await
+            .map_err(|_| ChannelRequestError::Closed)
+    }
+}
 
 #[derive(Debug)]
 pub enum ChannelMessage {
     /// JSON message
-    Json(String),
+    Json(Value),
     /// Debug log
     Debug(String),
     /// Warn log
@@ -29,21 +297,35 @@ pub enum ChannelMessage {
     /// Dump log
     Dump(String),
     /// Unknown
-    Unknown { command: u8, data: Vec<u8> },
+    Unknown { command: u8, data: Bytes },
 }
 
-fn deserialize_message(command: u8, data: Vec<u8>) -> ChannelMessage {
+fn deserialize_message(command: u8, data: Bytes) -> ChannelMessage {
     match command {
-        // JSON message
-        b'{' => ChannelMessage::Json(unsafe { String::from_utf8_unchecked(data) }),
+        // JSON message; `command` (the leading `{`) was split off of `data` by the caller, so it
+        // has to be stitched back on before parsing, which copies the JSON text once into a
+        // buffer with `command` in front. Only the non-JSON branches below keep `data` zero-copy.
+        b'{' => {
+            let mut json_bytes = Vec::with_capacity(data.len() + 1);
+            json_bytes.push(command);
+            json_bytes.extend_from_slice(&data);
+
+            match serde_json::from_slice(&json_bytes) {
+                Ok(value) => ChannelMessage::Json(value),
+                Err(error) => {
+                    error!("Failed to parse channel message as JSON: {}", error);
+                    ChannelMessage::Json(Value::Null)
+                }
+            }
+        }
         // Debug log
-        b'D' => ChannelMessage::Debug(unsafe { String::from_utf8_unchecked(data) }),
+        b'D' => ChannelMessage::Debug(unsafe { String::from_utf8_unchecked(data.to_vec()) }),
         // Warn log
-        b'W' => ChannelMessage::Warn(unsafe { String::from_utf8_unchecked(data) }),
+        b'W' => ChannelMessage::Warn(unsafe { String::from_utf8_unchecked(data.to_vec()) }),
         // Error log
-        b'E' => ChannelMessage::Error(unsafe { String::from_utf8_unchecked(data) }),
+        b'E' => ChannelMessage::Error(unsafe { String::from_utf8_unchecked(data.to_vec()) }),
         // Dump log
-        b'X' => ChannelMessage::Dump(unsafe { String::from_utf8_unchecked(data) }),
+        b'X' => ChannelMessage::Dump(unsafe { String::from_utf8_unchecked(data.to_vec()) }),
         // Unknown
         _ => ChannelMessage::Unknown { command, data },
     }
@@ -53,27 +335,57 @@ fn create_channel_pair(
     executor: &Executor,
     reader: AsyncFile,
     mut writer: AsyncFile,
-) -> (Sender<Vec<u8>>, Receiver<ChannelMessage>) {
+) -> (RequestSender, Receiver<ChannelMessage>) {
+    let inflight =
+        Arc::<Mutex<HashMap<u32, Sender<Result<ChannelMessage, ChannelRequestError>>>>>::default();
+
     let receiver = {
-        let (sender, receiver) = async_channel::bounded(1);
+        // Unbounded: a slow notification subscriber must not stall the reader task and, in turn,
+        // every in-flight `RequestSender::request()` waiting on it to demultiplex their replies.
+        let (sender, receiver) = async_channel::unbounded();
+        let inflight = Arc::clone(&inflight);
 
         executor
             .spawn(async move {
-                let mut bytes = vec![0u8; NS_PAYLOAD_MAX_LEN];
+                // Length-prefix scratch buffer; small and reused, so a `Vec<u8>` (required by
+                // `AsyncBufReadExt::read_until`) is fine here even though payloads are `Bytes`.
+                let mut length_bytes = Vec::new();
+                // Accumulating payload buffer; grows on demand instead of being capped up front,
+                // and `split_to` below hands out zero-copy, refcounted `Bytes` slices of it.
+                let mut payload_buf = BytesMut::with_capacity(NS_PAYLOAD_INITIAL_CAPACITY);
                 let mut reader = BufReader::new(reader);
 
                 loop {
-                    let read_bytes = reader.read_until(b':', &mut bytes).await?;
-                    bytes.pop();
-                    let length = String::from_utf8_lossy(&bytes[..read_bytes])
+                    length_bytes.clear();
+                    reader.read_until(b':', &mut length_bytes).await?;
+                    length_bytes.pop();
+                    let length = String::from_utf8_lossy(&length_bytes)
                         .parse::<usize>()
                         .unwrap();
+
                     // +1 because of netstring's `,` at the very end
-                    reader.read_exact(&mut bytes[..(length + 1)]).await?;
-                    // TODO: Parse messages here and send parsed messages over the channel
-                    let message = deserialize_message(bytes[0], Vec::from(&bytes[1..length]));
-                    println!("Received");
-                    let _ = sender.send(message);
+                    payload_buf.resize(length + 1, 0);
+                    reader.read_exact(&mut payload_buf[..(length + 1)]).await?;
+                    let frame = payload_buf.split_to(length + 1).freeze();
+                    let command = frame[0];
+                    let data = frame.slice(1..length);
+                    let message = deserialize_message(command, data);
+
+                    // A JSON message carrying an `id` is a reply to a still-outstanding request;
+                    // complete its waiting oneshot instead of handing it to the notification
+                    // receiver. Everything else (notifications, logs) takes the old path.
+                    if let ChannelMessage::Json(value) = &message {
+                        let id = value.get("id").and_then(Value::as_u64).map(|id| id as u32);
+
+                        if let Some(response_sender) =
+                            id.and_then(|id| inflight.lock().unwrap().remove(&id))
+                        {
+                            let _ = response_sender.send(Ok(message)).await;
+                            continue;
+                        }
+                    }
+
+                    let _ = sender.send(message).await;
                 }
 
                 io::Result::Ok(())
@@ -84,18 +396,97 @@ fn create_channel_pair(
     };
 
     let sender = {
-        let (sender, receiver) = async_channel::bounded::<Vec<u8>>(1);
+        type Queued = (Option<OrderTag>, Vec<u8>);
+
+        let (high_sender, high_receiver) = async_channel::unbounded::<Queued>();
+        let (normal_sender, normal_receiver) = async_channel::unbounded::<Queued>();
+        let (background_sender, background_receiver) = async_channel::unbounded::<Queued>();
 
         executor
             .spawn(async move {
-                let mut bytes = Vec::with_capacity(NS_PAYLOAD_MAX_LEN);
-                // TODO: Stringify messages here and received non-stringified messages over the
-                //  channel
-                while let Ok(message) = receiver.recv().await {
+                let mut bytes = Vec::with_capacity(NS_PAYLOAD_INITIAL_CAPACITY);
+                let mut high_queue: VecDeque<QueuedMessage> = VecDeque::new();
+                let mut normal_queue: VecDeque<QueuedMessage> = VecDeque::new();
+                let mut background_queue: VecDeque<QueuedMessage> = VecDeque::new();
+                let mut pending: PendingSeqs = HashMap::new();
+
+                fn enqueue(
+                    queue: &mut VecDeque<QueuedMessage>,
+                    pending: &mut PendingSeqs,
+                    order_tag: Option<OrderTag>,
+                    payload: Vec<u8>,
+                ) {
+                    if let Some(OrderTag { stream_id, seq }) = order_tag {
+                        pending.entry(stream_id).or_default().insert(seq);
+                    }
+                    queue.push_back(chunk_payload(order_tag, payload));
+                }
+
+                loop {
+                    // Pull in anything that arrived since the last turn without blocking, so a
+                    // burst of high-priority requests is seen before the next chunk is chosen.
+                    while let Ok((order_tag, message)) = high_receiver.try_recv() {
+                        enqueue(&mut high_queue, &mut pending, order_tag, message);
+                    }
+                    while let Ok((order_tag, message)) = normal_receiver.try_recv() {
+                        enqueue(&mut normal_queue, &mut pending, order_tag, message);
+                    }
+                    while let Ok((order_tag, message)) = background_receiver.try_recv() {
+                        enqueue(&mut background_queue, &mut pending, order_tag, message);
+                    }
+
+                    let chunk = match pop_next_chunk(
+                        &mut high_queue,
+                        &mut normal_queue,
+                        &mut background_queue,
+                        &mut pending,
+                    ) {
+                        Some(chunk) => chunk,
+                        None => {
+                            // Nothing ready to send; block until the first message of any
+                            // priority arrives, then requeue it and go around to pick it up
+                            // above (it may not be the one that's ready, if it's order-tagged).
+                            let received = futures_lite::future::or(
+                                futures_lite::future::or(
+                                    async { (RequestPriority::HIGH, high_receiver.recv().await) },
+                                    async {
+                                        (RequestPriority::NORMAL, normal_receiver.recv().await)
+                                    },
+                                ),
+                                async {
+                                    (RequestPriority::BACKGROUND, background_receiver.recv().await)
+                                },
+                            )
+                            .await;
+
+                            let (priority, (order_tag, message)) = match received {
+                                (priority, Ok(message)) => (priority, message),
+                                (_, Err(_)) => break,
+                            };
+
+                            match priority {
+                                RequestPriority::HIGH => {
+                                    enqueue(&mut high_queue, &mut pending, order_tag, message);
+                                }
+                                RequestPriority::NORMAL => {
+                                    enqueue(&mut normal_queue, &mut pending, order_tag, message);
+                                }
+                                _ => enqueue(
+                                    &mut background_queue,
+                                    &mut pending,
+                                    order_tag,
+                                    message,
+                                ),
+                            }
+
+                            continue;
+                        }
+                    };
+
                     bytes.clear();
-                    bytes.extend_from_slice(message.len().to_string().as_bytes());
+                    bytes.extend_from_slice(chunk.len().to_string().as_bytes());
                     bytes.push(b':');
-                    bytes.extend_from_slice(&message);
+                    bytes.extend_from_slice(&chunk);
                     bytes.push(b',');
 
                     writer.write_all(&bytes).await?;
@@ -105,67 +496,358 @@ fn create_channel_pair(
             })
             .detach();
 
-        sender
+        PrioritizedSender {
+            high: high_sender,
+            normal: normal_sender,
+            background: background_sender,
+        }
+    };
+
+    let request_sender = RequestSender {
+        sender,
+        next_id: Arc::new(AtomicU32::new(0)),
+        inflight,
     };
 
-    (sender, receiver)
+    (request_sender, receiver)
 }
 
 pub struct WorkerChannels {
-    pub channel: (Sender<Vec<u8>>, Receiver<ChannelMessage>),
-    pub payload_channel: (Sender<Vec<u8>>, Receiver<ChannelMessage>),
+    pub channel: (RequestSender, Receiver<ChannelMessage>),
+    pub payload_channel: (RequestSender, Receiver<ChannelMessage>),
 }
 
-pub fn setup_worker_channels(executor: &Executor, command: &mut Command) -> WorkerChannels {
-    let (producer_fd_read, producer_fd_write) = unistd::pipe().expect("Failed to create pipe");
-    let (consumer_fd_read, consumer_fd_write) = unistd::pipe().expect("Failed to create pipe");
-    let (producer_payload_fd_read, producer_payload_fd_write) =
-        unistd::pipe().expect("Failed to create pipe");
-    let (consumer_payload_fd_read, consumer_payload_fd_write) =
-        unistd::pipe().expect("Failed to create pipe");
-
-    unsafe {
-        command.pre_exec(move || {
-            unistd::dup2(producer_fd_read, 3).expect("Failed to duplicate fd");
-            unistd::dup2(consumer_fd_write, 4).expect("Failed to duplicate fd");
-            unistd::dup2(producer_payload_fd_read, 5).expect("Failed to duplicate fd");
-            unistd::dup2(consumer_payload_fd_write, 6).expect("Failed to duplicate fd");
-            // Duplicated above
-            unistd::close(producer_fd_read).expect("Failed to close fd");
-            unistd::close(consumer_fd_write).expect("Failed to close fd");
-            unistd::close(producer_payload_fd_read).expect("Failed to close fd");
-            unistd::close(consumer_payload_fd_write).expect("Failed to close fd");
-            // Unused in child
-            unistd::close(producer_fd_write).expect("Failed to close fd");
-            unistd::close(consumer_fd_read).expect("Failed to close fd");
-            unistd::close(producer_payload_fd_write).expect("Failed to close fd");
-            unistd::close(consumer_payload_fd_read).expect("Failed to close fd");
-
-            Ok(())
-        });
-    };
+/// Abstracts the four duplex byte streams a worker process communicates over (channel and
+/// payload channel, each a reader/writer pair), so [`create_channel_pair`]'s netstring framing
+/// doesn't need to know whether they're backed by Unix pipes or another platform's IPC primitive.
+pub trait WorkerTransport {
+    /// Wires `command` up to communicate over this transport and returns this process's local
+    /// ends of each stream, in the order `setup_worker_channels` expects them: channel
+    /// reader/writer, then payload channel reader/writer.
+    fn setup(command: &mut Command) -> (AsyncFile, AsyncFile, AsyncFile, AsyncFile);
+}
+
+/// Default [`WorkerTransport`] on Unix: four pipes, with the worker's ends duplicated onto file
+/// descriptors 3-6 right before `exec`, which is the convention the mediasoup worker binary
+/// expects.
+#[cfg(unix)]
+pub struct UnixPipeTransport;
+
+#[cfg(unix)]
+impl WorkerTransport for UnixPipeTransport {
+    fn setup(command: &mut Command) -> (AsyncFile, AsyncFile, AsyncFile, AsyncFile) {
+        let (producer_fd_read, producer_fd_write) = unistd::pipe().expect("Failed to create pipe");
+        let (consumer_fd_read, consumer_fd_write) = unistd::pipe().expect("Failed to create pipe");
+        let (producer_payload_fd_read, producer_payload_fd_write) =
+            unistd::pipe().expect("Failed to create pipe");
+        let (consumer_payload_fd_read, consumer_payload_fd_write) =
+            unistd::pipe().expect("Failed to create pipe");
+
+        unsafe {
+            command.pre_exec(move || {
+                unistd::dup2(producer_fd_read, 3).expect("Failed to duplicate fd");
+                unistd::dup2(consumer_fd_write, 4).expect("Failed to duplicate fd");
+                unistd::dup2(producer_payload_fd_read, 5).expect("Failed to duplicate fd");
+                unistd::dup2(consumer_payload_fd_write, 6).expect("Failed to duplicate fd");
+                // Duplicated above
+                unistd::close(producer_fd_read).expect("Failed to close fd");
+                unistd::close(consumer_fd_write).expect("Failed to close fd");
+                unistd::close(producer_payload_fd_read).expect("Failed to close fd");
+                unistd::close(consumer_payload_fd_write).expect("Failed to close fd");
+                // Unused in child
+                unistd::close(producer_fd_write).expect("Failed to close fd");
+                unistd::close(consumer_fd_read).expect("Failed to close fd");
+                unistd::close(producer_payload_fd_write).expect("Failed to close fd");
+                unistd::close(consumer_payload_fd_read).expect("Failed to close fd");
+
+                Ok(())
+            });
+        };
+
+        // Unused in parent
+        unistd::close(producer_fd_read).expect("Failed to close fd");
+        unistd::close(consumer_fd_write).expect("Failed to close fd");
+        unistd::close(producer_payload_fd_read).expect("Failed to close fd");
+        unistd::close(consumer_payload_fd_write).expect("Failed to close fd");
+
+        let producer_file: AsyncFile = unsafe { StdFile::from_raw_fd(producer_fd_write) }.into();
+        let consumer_file: AsyncFile = unsafe { StdFile::from_raw_fd(consumer_fd_read) }.into();
+        let producer_payload_file: AsyncFile =
+            unsafe { StdFile::from_raw_fd(producer_payload_fd_write) }.into();
+        let consumer_payload_file: AsyncFile =
+            unsafe { StdFile::from_raw_fd(consumer_payload_fd_read) }.into();
+
+        (
+            consumer_file,
+            producer_file,
+            consumer_payload_file,
+            producer_payload_file,
+        )
+    }
+}
+
+#[cfg(windows)]
+pub use windows_transport::WindowsNamedPipeTransport;
+
+/// Named-pipe-backed [`WorkerTransport`] for platforms without Unix pipe/fd inheritance, using
+/// the same "create a uniquely named pipe, hand its name to the child" approach ethers-rs and
+/// discord-rpc-client use for their own IPC transports, instead of inheriting file descriptors
+/// 3-6 across `exec`.
+#[cfg(windows)]
+mod windows_transport {
+    use super::{AsyncFile, Command, WorkerTransport};
+    use std::ffi::OsStr;
+    use std::fs::File as StdFile;
+    use std::io;
+    use std::os::raw::c_void;
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::{FromRawHandle, RawHandle};
+
+    type Handle = *mut c_void;
+
+    const INVALID_HANDLE_VALUE: Handle = -1isize as Handle;
+    const PIPE_ACCESS_DUPLEX: u32 = 0x0000_0003;
+    const PIPE_TYPE_BYTE: u32 = 0x0000_0000;
+    const PIPE_READMODE_BYTE: u32 = 0x0000_0000;
+    const PIPE_WAIT: u32 = 0x0000_0000;
+    const PIPE_UNLIMITED_INSTANCES: u32 = 255;
+    const PIPE_BUFFER_SIZE: u32 = 65536;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateNamedPipeW(
+            lp_name: *const u16,
+            dw_open_mode: u32,
+            dw_pipe_mode: u32,
+            n_max_instances: u32,
+            n_out_buffer_size: u32,
+            n_in_buffer_size: u32,
+            n_default_time_out: u32,
+            lp_security_attributes: *mut c_void,
+        ) -> Handle;
+    }
+
+    fn wide_null(value: &str) -> Vec<u16> {
+        OsStr::new(value)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect()
+    }
+
+    /// Creates a unique duplex named pipe under `\\.\pipe\` and returns this process's end along
+    /// with the name the worker should connect to.
+    fn create_named_pipe(label: &str) -> io::Result<(AsyncFile, String)> {
+        let name = format!(r"\\.\pipe\mediasoup-{}-{}", label, std::process::id());
+        let wide_name = wide_null(&name);
+
+        let handle = unsafe {
+            CreateNamedPipeW(
+                wide_name.as_ptr(),
+                PIPE_ACCESS_DUPLEX,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                PIPE_UNLIMITED_INSTANCES,
+                PIPE_BUFFER_SIZE,
+                PIPE_BUFFER_SIZE,
+                0,
+                std::ptr::null_mut(),
+            )
+        };
+
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+
+        let file: AsyncFile = unsafe { StdFile::from_raw_handle(handle as RawHandle) }.into();
+        Ok((file, name))
+    }
+
+    pub struct WindowsNamedPipeTransport;
+
+    impl WorkerTransport for WindowsNamedPipeTransport {
+        fn setup(command: &mut Command) -> (AsyncFile, AsyncFile, AsyncFile, AsyncFile) {
+            let (consumer_file, producer_pipe_name) =
+                create_named_pipe("channel-out").expect("Failed to create named pipe");
+            let (producer_file, consumer_pipe_name) =
+                create_named_pipe("channel-in").expect("Failed to create named pipe");
+            let (consumer_payload_file, producer_payload_pipe_name) =
+                create_named_pipe("payload-channel-out").expect("Failed to create named pipe");
+            let (producer_payload_file, consumer_payload_pipe_name) =
+                create_named_pipe("payload-channel-in").expect("Failed to create named pipe");
+
+            // The worker connects to each pipe by name instead of inheriting fds 3-6.
+            command
+                .env("MEDIASOUP_CHANNEL_IN_PIPE", consumer_pipe_name)
+                .env("MEDIASOUP_CHANNEL_OUT_PIPE", producer_pipe_name)
+                .env(
+                    "MEDIASOUP_PAYLOAD_CHANNEL_IN_PIPE",
+                    consumer_payload_pipe_name,
+                )
+                .env(
+                    "MEDIASOUP_PAYLOAD_CHANNEL_OUT_PIPE",
+                    producer_payload_pipe_name,
+                );
+
+            (
+                consumer_file,
+                producer_file,
+                consumer_payload_file,
+                producer_payload_file,
+            )
+        }
+    }
+}
+
+#[cfg(unix)]
+type DefaultWorkerTransport = UnixPipeTransport;
+#[cfg(windows)]
+type DefaultWorkerTransport = WindowsNamedPipeTransport;
 
-    let producer_file: AsyncFile;
-    let consumer_file: AsyncFile;
-    let producer_payload_file: AsyncFile;
-    let consumer_payload_file: AsyncFile;
-    // Unused in parent
-    unistd::close(producer_fd_read).expect("Failed to close fd");
-    unistd::close(consumer_fd_write).expect("Failed to close fd");
-    unistd::close(producer_payload_fd_read).expect("Failed to close fd");
-    unistd::close(consumer_payload_fd_write).expect("Failed to close fd");
-
-    producer_file = unsafe { StdFile::from_raw_fd(producer_fd_write) }.into();
-    consumer_file = unsafe { StdFile::from_raw_fd(consumer_fd_read) }.into();
-    producer_payload_file = unsafe { StdFile::from_raw_fd(producer_payload_fd_write) }.into();
-    consumer_payload_file = unsafe { StdFile::from_raw_fd(consumer_payload_fd_read) }.into();
+pub fn setup_worker_channels(executor: &Executor, command: &mut Command) -> WorkerChannels {
+    let (consumer_file, producer_file, consumer_payload_file, producer_payload_file) =
+        DefaultWorkerTransport::setup(command);
 
     WorkerChannels {
-        channel: create_channel_pair(&executor, consumer_file, producer_file),
+        channel: create_channel_pair(executor, consumer_file, producer_file),
         payload_channel: create_channel_pair(
-            &executor,
+            executor,
             consumer_payload_file,
             producer_payload_file,
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{chunk_payload, is_ready, pop_next_chunk, OrderTag, PendingSeqs};
+    use std::collections::{BTreeSet, VecDeque};
+
+    fn queue_of(messages: Vec<(Option<OrderTag>, Vec<u8>)>) -> VecDeque<super::QueuedMessage> {
+        messages
+            .into_iter()
+            .map(|(order_tag, payload)| chunk_payload(order_tag, payload))
+            .collect()
+    }
+
+    fn pending_with(stream_id: u64, seqs: impl IntoIterator<Item = u64>) -> PendingSeqs {
+        let mut pending = PendingSeqs::new();
+        pending.insert(stream_id, seqs.into_iter().collect());
+        pending
+    }
+
+    #[test]
+    fn untagged_message_is_always_ready() {
+        let message = chunk_payload(None, vec![1]);
+        assert!(is_ready(&message, &PendingSeqs::new()));
+    }
+
+    #[test]
+    fn tagged_message_is_ready_only_once_it_is_the_oldest_pending_seq() {
+        let pending = pending_with(1, [2, 5]);
+
+        let oldest = chunk_payload(Some(OrderTag::new(1, 2)), vec![1]);
+        assert!(is_ready(&oldest, &pending));
+
+        let newer = chunk_payload(Some(OrderTag::new(1, 5)), vec![1]);
+        assert!(!is_ready(&newer, &pending));
+    }
+
+    #[test]
+    fn tagged_message_is_ready_when_its_stream_has_no_other_pending_seqs() {
+        let message = chunk_payload(Some(OrderTag::new(1, 7)), vec![1]);
+        assert!(is_ready(&message, &PendingSeqs::new()));
+    }
+
+    #[test]
+    fn pop_next_chunk_prefers_higher_priority_queue() {
+        let mut high = queue_of(vec![(None, vec![1])]);
+        let mut normal = queue_of(vec![(None, vec![2])]);
+        let mut background = queue_of(vec![(None, vec![3])]);
+        let mut pending = PendingSeqs::new();
+
+        let chunk = pop_next_chunk(&mut high, &mut normal, &mut background, &mut pending);
+        assert_eq!(chunk, Some(vec![1]));
+        assert!(high.is_empty());
+        assert_eq!(normal.len(), 1);
+    }
+
+    #[test]
+    fn pop_next_chunk_skips_a_not_ready_front_message_for_a_lower_priority_queue() {
+        let mut high = queue_of(vec![(Some(OrderTag::new(1, 5)), vec![1])]);
+        let mut normal = queue_of(vec![(None, vec![2])]);
+        let mut background = VecDeque::new();
+        let mut pending = pending_with(1, [2, 5]);
+
+        let chunk = pop_next_chunk(&mut high, &mut normal, &mut background, &mut pending);
+        assert_eq!(chunk, Some(vec![2]));
+        assert_eq!(high.len(), 1);
+        assert!(normal.is_empty());
+    }
+
+    #[test]
+    fn pop_next_chunk_skips_a_not_ready_front_message_for_a_ready_one_behind_it_in_the_same_queue()
+    {
+        let mut high = queue_of(vec![
+            (Some(OrderTag::new(1, 5)), vec![1]),
+            (None, vec![2]),
+        ]);
+        let mut normal = VecDeque::new();
+        let mut background = VecDeque::new();
+        let mut pending = pending_with(1, [2, 5]);
+
+        let chunk = pop_next_chunk(&mut high, &mut normal, &mut background, &mut pending);
+        assert_eq!(chunk, Some(vec![2]));
+        assert_eq!(high.len(), 1);
+        assert_eq!(high.front().unwrap().order_tag, Some(OrderTag::new(1, 5)));
+    }
+
+    #[test]
+    fn pop_next_chunk_rotates_a_multi_chunk_message_to_the_back_of_its_queue() {
+        let mut high = VecDeque::new();
+        let mut normal = queue_of(vec![
+            (None, vec![0; super::ROUND_ROBIN_CHUNK_SIZE + 1]),
+            (None, vec![9]),
+        ]);
+        let mut background = VecDeque::new();
+        let mut pending = PendingSeqs::new();
+
+        let first = pop_next_chunk(&mut high, &mut normal, &mut background, &mut pending);
+        assert_eq!(first, Some(vec![0; super::ROUND_ROBIN_CHUNK_SIZE]));
+        assert_eq!(normal.len(), 2);
+
+        let second = pop_next_chunk(&mut high, &mut normal, &mut background, &mut pending);
+        assert_eq!(second, Some(vec![9]));
+        assert_eq!(normal.len(), 1);
+
+        let third = pop_next_chunk(&mut high, &mut normal, &mut background, &mut pending);
+        assert_eq!(third, Some(vec![0]));
+        assert!(normal.is_empty());
+    }
+
+    #[test]
+    fn pop_next_chunk_clears_the_seq_from_pending_once_the_tagged_message_is_fully_sent() {
+        let mut high = VecDeque::new();
+        let mut normal = queue_of(vec![(Some(OrderTag::new(1, 2)), vec![1])]);
+        let mut background = VecDeque::new();
+        let mut pending = pending_with(1, [2, 5]);
+
+        let chunk = pop_next_chunk(&mut high, &mut normal, &mut background, &mut pending);
+        assert_eq!(chunk, Some(vec![1]));
+        assert_eq!(
+            pending.get(&1).cloned().unwrap_or_default(),
+            [5].into_iter().collect::<BTreeSet<_>>()
+        );
+    }
+
+    #[test]
+    fn pop_next_chunk_returns_none_when_all_queues_are_empty() {
+        let mut high = VecDeque::new();
+        let mut normal = VecDeque::new();
+        let mut background = VecDeque::new();
+        let mut pending = PendingSeqs::new();
+
+        assert_eq!(
+            pop_next_chunk(&mut high, &mut normal, &mut background, &mut pending),
+            None
+        );
+    }
+}
@@ -0,0 +1,450 @@
+//! The worker channel and payload channel: the two netstring-framed pipes a worker process is
+//! driven over. [`channels`] implements the low-level multiplexed request/response transport;
+//! this module layers [`Channel`]/[`PayloadChannel`] on top, adding JSON (de)serialization,
+//! per-request timeout/cancellation and notification dispatch.
+
+pub mod channels;
+pub mod request_options;
+
+use crate::worker::channels::{
+    ChannelMessage, ChannelRequestError, RequestPriority, RequestSender,
+};
+use crate::worker::request_options::RequestOptions;
+use async_channel::Receiver;
+use async_executor::Executor;
+use async_io::Timer;
+use bytes::Bytes;
+use log::{debug, error, warn};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+/// Error produced by [`Channel::request`]/[`Channel::request_with`] (and their
+/// [`PayloadChannel`] equivalents).
+#[derive(Debug, Error)]
+pub enum RequestError {
+    /// The worker didn't reply within the request's [`RequestOptions::timeout`].
+    #[error("request timed out")]
+    TimedOut,
+    /// `RequestOptions::cancellation` resolved before the worker replied.
+    #[error("request cancelled")]
+    Cancelled,
+    /// The channel's reader task exited (worker process gone) before a reply arrived, or a later
+    /// request reused this request's `id` while it was still in flight.
+    #[error("channel closed before a response arrived")]
+    ChannelClosed,
+    /// The worker rejected the request.
+    #[error("worker rejected request: {reason}")]
+    Response { reason: String },
+    /// The worker replied with something other than a JSON message.
+    #[error("worker sent a non-JSON reply to a request")]
+    UnexpectedMessage,
+    /// The request couldn't be serialized, or the reply's `data` couldn't be deserialized into
+    /// the expected type.
+    #[error("failed to (de)serialize request/response: {0}")]
+    FailedToParse(#[from] serde_json::Error),
+}
+
+impl From<ChannelRequestError> for RequestError {
+    fn from(_error: ChannelRequestError) -> Self {
+        RequestError::ChannelClosed
+    }
+}
+
+/// Error produced by [`PayloadChannel::notify`].
+#[derive(Debug, Error)]
+pub enum NotificationError {
+    /// The notification's header couldn't be serialized.
+    #[error("failed to serialize notification: {0}")]
+    FailedToSerialize(#[from] serde_json::Error),
+    /// The payload channel's reader/writer task exited before the notification could be sent.
+    #[error("payload channel closed before notification could be sent")]
+    ChannelClosed,
+}
+
+impl From<ChannelRequestError> for NotificationError {
+    fn from(_error: ChannelRequestError) -> Self {
+        NotificationError::ChannelClosed
+    }
+}
+
+/// A payload channel notification: its JSON header plus the binary payload that immediately
+/// followed it on the wire.
+#[derive(Debug, Clone)]
+pub struct NotificationMessage {
+    /// The notification's JSON header (its `event`/`data` fields, see each entity's local
+    /// `PayloadNotification` enum).
+    pub message: Value,
+    /// The binary frame that followed the header.
+    pub payload: Bytes,
+}
+
+/// Drop guard returned by [`Channel::subscribe_to_notifications`]/
+/// [`PayloadChannel::subscribe_to_notifications`]: deregisters the subscriber on drop so no
+/// further notifications are dispatched to a vanished callback.
+pub struct SubscriptionHandler {
+    remove: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl SubscriptionHandler {
+    fn new(remove: impl FnOnce() + Send + 'static) -> Self {
+        Self {
+            remove: Some(Box::new(remove)),
+        }
+    }
+}
+
+impl Drop for SubscriptionHandler {
+    fn drop(&mut self) {
+        if let Some(remove) = self.remove.take() {
+            remove();
+        }
+    }
+}
+
+/// Races `pending` (a [`RequestSender::request`] call already under way) against `options`'
+/// timeout and, if set, cancellation future, then decodes the winning [`ChannelMessage`] into
+/// `Res`.
+async fn request_with_impl<Req, Res>(
+    request_sender: &RequestSender,
+    request: Req,
+    options: RequestOptions,
+) -> Result<Res, RequestError>
+where
+    Req: Serialize,
+    Res: DeserializeOwned,
+{
+    let body = serde_json::to_value(&request)?;
+
+    let pending = request_sender.request(options.priority, options.order_tag, body);
+    let timed_out = async {
+        Timer::after(options.timeout).await;
+        Err(RequestError::TimedOut)
+    };
+
+    let message = match options.cancellation {
+        Some(cancellation) => {
+            let cancelled = async {
+                cancellation.await;
+                Err(RequestError::Cancelled)
+            };
+            futures_lite::future::or(
+                futures_lite::future::or(async { Ok(pending.await?) }, timed_out),
+                cancelled,
+            )
+            .await?
+        }
+        None => futures_lite::future::or(async { Ok(pending.await?) }, timed_out).await?,
+    };
+
+    parse_response(message)
+}
+
+/// Decodes a worker reply of the form `{"accepted": bool, "data": ..}` / `{"accepted": false,
+/// "reason": ..}` into `Res`.
+fn parse_response<Res: DeserializeOwned>(message: ChannelMessage) -> Result<Res, RequestError> {
+    let value = match message {
+        ChannelMessage::Json(value) => value,
+        _ => return Err(RequestError::UnexpectedMessage),
+    };
+
+    let accepted = value
+        .get("accepted")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    if !accepted {
+        let reason = value
+            .get("reason")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown reason")
+            .to_string();
+        return Err(RequestError::Response { reason });
+    }
+
+    let data = value.get("data").cloned().unwrap_or(Value::Null);
+    serde_json::from_value(data).map_err(RequestError::FailedToParse)
+}
+
+/// Logs a non-reply, non-notification message the same way across [`Channel`] and
+/// [`PayloadChannel`]'s dispatch tasks.
+fn log_message(message: &ChannelMessage) {
+    match message {
+        ChannelMessage::Debug(text) => debug!("{}", text),
+        ChannelMessage::Warn(text) => warn!("{}", text),
+        ChannelMessage::Error(text) => error!("{}", text),
+        ChannelMessage::Dump(text) => debug!("{}", text),
+        ChannelMessage::Json(_) | ChannelMessage::Unknown { .. } => {}
+    }
+}
+
+type NotificationCallback = Arc<dyn Fn(Value) + Send + Sync>;
+
+/// The worker's main channel: JSON requests/replies and JSON notifications (`targetId`-keyed
+/// events with no associated binary payload).
+#[derive(Clone)]
+pub struct Channel {
+    request_sender: RequestSender,
+    subscribers: Arc<Mutex<HashMap<String, NotificationCallback>>>,
+}
+
+impl Channel {
+    /// Wraps `request_sender`, spawning a task on `executor` that dispatches everything arriving
+    /// on `notifications` (messages that aren't replies to an in-flight request, see
+    /// [`channels::create_channel_pair`]) to whichever subscriber's `targetId` it carries.
+    pub(crate) fn new(
+        executor: &Executor<'static>,
+        request_sender: RequestSender,
+        notifications: Receiver<ChannelMessage>,
+    ) -> Self {
+        let subscribers: Arc<Mutex<HashMap<String, NotificationCallback>>> = Arc::default();
+
+        {
+            let subscribers = Arc::clone(&subscribers);
+            executor
+                .spawn(async move {
+                    while let Ok(message) = notifications.recv().await {
+                        match &message {
+                            ChannelMessage::Json(value) => {
+                                let callback = value
+                                    .get("targetId")
+                                    .and_then(Value::as_str)
+                                    .and_then(|id| subscribers.lock().unwrap().get(id).cloned());
+
+                                if let Some(callback) = callback {
+                                    callback(value.clone());
+                                }
+                            }
+                            _ => log_message(&message),
+                        }
+                    }
+                })
+                .detach();
+        }
+
+        Self {
+            request_sender,
+            subscribers,
+        }
+    }
+
+    /// Subscribes `callback` to notifications carrying `target_id` until the returned
+    /// [`SubscriptionHandler`] is dropped.
+    pub async fn subscribe_to_notifications<F: Fn(Value) + Send + Sync + 'static>(
+        &self,
+        target_id: String,
+        callback: F,
+    ) -> SubscriptionHandler {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .insert(target_id.clone(), Arc::new(callback));
+
+        let subscribers = Arc::clone(&self.subscribers);
+        SubscriptionHandler::new(move || {
+            subscribers.lock().unwrap().remove(&target_id);
+        })
+    }
+
+    /// Sends `request` and waits for the worker's reply, bounded by
+    /// [`request_options::DEFAULT_REQUEST_TIMEOUT`].
+    pub async fn request<Req, Res>(&self, request: Req) -> Result<Res, RequestError>
+    where
+        Req: Serialize,
+        Res: DeserializeOwned,
+    {
+        self.request_with(request, RequestOptions::default()).await
+    }
+
+    /// Same as [`Self::request`], but the request fails with [`RequestError::TimedOut`] (or
+    /// [`RequestError::Cancelled`]) according to `options` instead of waiting on the worker
+    /// indefinitely.
+    pub async fn request_with<Req, Res>(
+        &self,
+        request: Req,
+        options: RequestOptions,
+    ) -> Result<Res, RequestError>
+    where
+        Req: Serialize,
+        Res: DeserializeOwned,
+    {
+        request_with_impl(&self.request_sender, request, options).await
+    }
+}
+
+type PayloadNotificationCallback = Arc<dyn Fn(NotificationMessage) + Send + Sync>;
+
+/// A minimal async mutex built on a single-permit [`async_channel`], used to keep a payload
+/// notification's header and binary payload frames adjacent on the wire despite concurrent
+/// [`PayloadChannel::notify`] calls.
+struct SendLock {
+    permit: (async_channel::Sender<()>, async_channel::Receiver<()>),
+}
+
+impl SendLock {
+    fn new() -> Self {
+        let permit = async_channel::bounded(1);
+        permit
+            .0
+            .try_send(())
+            .expect("freshly created bounded(1) channel always has room");
+        Self { permit }
+    }
+
+    async fn lock(&self) -> SendLockGuard<'_> {
+        self.permit
+            .1
+            .recv()
+            .await
+            .expect("sender kept alive by self.permit.0");
+        SendLockGuard { lock: self }
+    }
+}
+
+struct SendLockGuard<'a> {
+    lock: &'a SendLock,
+}
+
+impl Drop for SendLockGuard<'_> {
+    fn drop(&mut self) {
+        let _ = self.lock.permit.0.try_send(());
+    }
+}
+
+/// The worker's payload channel: JSON requests/replies like [`Channel`], plus notifications that
+/// carry a binary payload alongside their JSON header (e.g. RTP packets, DataChannel messages).
+#[derive(Clone)]
+pub struct PayloadChannel {
+    request_sender: RequestSender,
+    subscribers: Arc<Mutex<HashMap<String, PayloadNotificationCallback>>>,
+    send_lock: Arc<SendLock>,
+}
+
+impl PayloadChannel {
+    /// Same as [`Channel::new`], but pairs each JSON header with the binary frame immediately
+    /// following it before dispatching a [`NotificationMessage`] to the matching subscriber.
+    pub(crate) fn new(
+        executor: &Executor<'static>,
+        request_sender: RequestSender,
+        notifications: Receiver<ChannelMessage>,
+    ) -> Self {
+        let subscribers: Arc<Mutex<HashMap<String, PayloadNotificationCallback>>> =
+            Arc::default();
+
+        {
+            let subscribers = Arc::clone(&subscribers);
+            executor
+                .spawn(async move {
+                    let mut pending_header: Option<Value> = None;
+
+                    while let Ok(message) = notifications.recv().await {
+                        match message {
+                            ChannelMessage::Json(value) => pending_header = Some(value),
+                            ChannelMessage::Unknown { data, .. } => {
+                                let header = match pending_header.take() {
+                                    Some(header) => header,
+                                    None => continue,
+                                };
+
+                                let callback = header
+                                    .get("targetId")
+                                    .and_then(Value::as_str)
+                                    .and_then(|id| subscribers.lock().unwrap().get(id).cloned());
+
+                                if let Some(callback) = callback {
+                                    callback(NotificationMessage {
+                                        message: header,
+                                        payload: data,
+                                    });
+                                }
+                            }
+                            other => log_message(&other),
+                        }
+                    }
+                })
+                .detach();
+        }
+
+        Self {
+            request_sender,
+            subscribers,
+            send_lock: Arc::new(SendLock::new()),
+        }
+    }
+
+    /// Subscribes `callback` to payload notifications carrying `target_id` until the returned
+    /// [`SubscriptionHandler`] is dropped.
+    pub async fn subscribe_to_notifications<F: Fn(NotificationMessage) + Send + Sync + 'static>(
+        &self,
+        target_id: String,
+        callback: F,
+    ) -> SubscriptionHandler {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .insert(target_id.clone(), Arc::new(callback));
+
+        let subscribers = Arc::clone(&self.subscribers);
+        SubscriptionHandler::new(move || {
+            subscribers.lock().unwrap().remove(&target_id);
+        })
+    }
+
+    /// Sends `request` and waits for the worker's reply, bounded by
+    /// [`request_options::DEFAULT_REQUEST_TIMEOUT`].
+    pub async fn request<Req, Res>(&self, request: Req) -> Result<Res, RequestError>
+    where
+        Req: Serialize,
+        Res: DeserializeOwned,
+    {
+        self.request_with(request, RequestOptions::default()).await
+    }
+
+    /// Same as [`Self::request`], but bounded by `options` instead.
+    pub async fn request_with<Req, Res>(
+        &self,
+        request: Req,
+        options: RequestOptions,
+    ) -> Result<Res, RequestError>
+    where
+        Req: Serialize,
+        Res: DeserializeOwned,
+    {
+        request_with_impl(&self.request_sender, request, options).await
+    }
+
+    /// Sends `notification`'s header immediately followed by `payload`, without waiting for a
+    /// reply, at [`RequestPriority::NORMAL`].
+    pub async fn notify<Req: Serialize>(
+        &self,
+        notification: Req,
+        payload: Bytes,
+    ) -> Result<(), NotificationError> {
+        self.notify_with(notification, payload, RequestPriority::default())
+            .await
+    }
+
+    /// Same as [`Self::notify`], but queued under `priority` instead, e.g.
+    /// [`RequestPriority::BACKGROUND`] for bulk data sends that should yield to control traffic.
+    /// The two frames are kept adjacent on the wire even under concurrent `notify`/`notify_with`
+    /// calls.
+    pub async fn notify_with<Req: Serialize>(
+        &self,
+        notification: Req,
+        payload: Bytes,
+        priority: RequestPriority,
+    ) -> Result<(), NotificationError> {
+        let body = serde_json::to_value(&notification)?;
+        let _guard = self.send_lock.lock().await;
+
+        self.request_sender.notify(priority, None, body).await?;
+        self.request_sender
+            .notify_raw(priority, None, payload.to_vec())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+//! Per-request options for `Channel::request`, so a single stalled worker request cannot hang
+//! its caller forever.
+
+use crate::worker::channels::{OrderTag, RequestPriority};
+use futures_lite::future::Boxed as BoxFuture;
+use std::time::Duration;
+
+/// Default time a request is allowed to wait for the worker's response before it is resolved
+/// with [`crate::worker::RequestError::TimedOut`].
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Options bound to a single `Channel::request` call.
+#[non_exhaustive]
+pub struct RequestOptions {
+    /// How long to wait for the worker's response before giving up.
+    pub timeout: Duration,
+    /// An optional future that, if it resolves before the response arrives, cancels the request.
+    pub cancellation: Option<BoxFuture<()>>,
+    /// The queueing priority this request competes at, e.g. [`RequestPriority::HIGH`] for control
+    /// operations that must never queue behind bulk data.
+    pub priority: RequestPriority,
+    /// An optional tag tying this request to others that must reach the worker in enqueue order.
+    pub order_tag: Option<OrderTag>,
+}
+
+impl std::fmt::Debug for RequestOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequestOptions")
+            .field("timeout", &self.timeout)
+            .field("cancellation", &self.cancellation.is_some())
+            .field("priority", &self.priority)
+            .field("order_tag", &self.order_tag)
+            .finish()
+    }
+}
+
+impl Default for RequestOptions {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+            cancellation: None,
+            priority: RequestPriority::default(),
+            order_tag: None,
+        }
+    }
+}
+
+impl RequestOptions {
+    /// Request options with a custom timeout and no cancellation.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            ..Self::default()
+        }
+    }
+
+    /// Request options cancelled when `cancellation` resolves, in addition to the timeout.
+    pub fn with_cancellation(timeout: Duration, cancellation: BoxFuture<()>) -> Self {
+        Self {
+            timeout,
+            cancellation: Some(cancellation),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the queueing priority these options request, e.g. [`RequestPriority::HIGH`] for a
+    /// control operation that must not queue behind bulk data.
+    pub fn with_priority(mut self, priority: RequestPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Ties this request to others sharing `order_tag`, forcing the worker to receive them in the
+    /// order they were enqueued regardless of priority.
+    pub fn with_order_tag(mut self, order_tag: OrderTag) -> Self {
+        self.order_tag = Some(order_tag);
+        self
+    }
+}